@@ -1,21 +1,616 @@
 // This is free and unencumbered software released into the public domain.
 
+use crate::browsers::ProfileBookmarks;
+use crate::{
+    BookmarkFolder, BookmarkNode, BookmarkStorage, BookmarkUrl, BookmarksFile, format_webkit_time,
+};
+use core::{fmt, str::FromStr};
+use miette::miette;
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+use std::collections::BTreeMap;
+use std::format;
+use std::string::String;
+use std::vec::Vec;
+
+#[cfg(feature = "jq")]
 use jq::{JsonFilter, JsonFilterError};
-use serde_json::Value;
 
 /// Transforms Chromium JSON bookmarks to JSON-LD.
+///
+/// By default the transform is implemented natively. With the `jq` feature
+/// enabled, a custom jq filter can be used instead.
+#[derive(Clone, Default)]
 pub struct BookmarksTransform {
-    filter: JsonFilter,
+    #[cfg(feature = "jq")]
+    filter: Option<JsonFilter>,
+    vocabulary: Vocabulary,
+}
+
+/// The vocabulary of the native JSON-LD output.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Vocabulary {
+    /// The KNOW ontology (`know:Bookmark`, `know:title`, `know:link`, ...).
+    #[default]
+    Know,
+    /// schema.org (`schema:WebPage`, `schema:name`, `schema:url`, ...), with
+    /// KNOW properties where schema.org has no equivalent.
+    Schema,
+    /// Both vocabularies side by side.
+    Both,
+}
+
+/// An error from [`BookmarksTransform`].
+#[derive(Debug)]
+pub enum TransformError {
+    /// The input is not a valid Chromium bookmarks document.
+    Json(serde_json::Error),
+    /// The jq filter failed to compile or execute.
+    #[cfg(feature = "jq")]
+    Filter(JsonFilterError),
 }
 
 impl BookmarksTransform {
-    pub fn new() -> Result<Self, JsonFilterError> {
-        Ok(Self {
-            filter: crate::jq::BOOKMARKS.parse()?,
+    pub fn new() -> Result<Self, TransformError> {
+        Ok(Self::default())
+    }
+
+    /// Creates a transform that runs the given jq filter instead of the
+    /// native implementation.
+    #[cfg(feature = "jq")]
+    pub fn with_filter(filter: JsonFilter) -> Self {
+        Self {
+            filter: Some(filter),
+            ..Default::default()
+        }
+    }
+
+    /// Creates a transform that runs the given jq program instead of the
+    /// native implementation.
+    #[cfg(feature = "jq")]
+    pub fn with_jq(program: &str) -> Result<Self, TransformError> {
+        Ok(Self::with_filter(program.parse()?))
+    }
+
+    /// Uses the given vocabulary for the native JSON-LD output.
+    pub fn with_vocabulary(mut self, vocabulary: Vocabulary) -> Self {
+        self.vocabulary = vocabulary;
+        self
+    }
+
+    pub fn vocabulary(&self) -> Vocabulary {
+        self.vocabulary
+    }
+
+    pub fn execute(&self, input: Value) -> Result<Value, TransformError> {
+        let bookmarks: BookmarksFile = serde_json::from_value(input)?;
+        self.execute_bookmarks(&bookmarks)
+    }
+
+    /// Transforms bookmarks of unknown origin, described by an anonymous
+    /// profile node.
+    pub fn execute_bookmarks(&self, bookmarks: &BookmarksFile) -> Result<Value, TransformError> {
+        self.transform(bookmarks, &Profile::default())
+    }
+
+    /// Transforms the bookmarks of a browser profile, described by a profile
+    /// node with the browser, profile name and source files.
+    pub fn execute_profile(&self, profile: &ProfileBookmarks) -> Result<Value, TransformError> {
+        self.transform(&profile.bookmarks, &Profile::from(profile))
+    }
+
+    fn transform(
+        &self,
+        bookmarks: &BookmarksFile,
+        profile: &Profile,
+    ) -> Result<Value, TransformError> {
+        // The jq filter finds the profile next to the Chromium roots:
+        #[cfg(feature = "jq")]
+        if let Some(filter) = &self.filter {
+            let mut input = serde_json::to_value(bookmarks)?;
+            for (root, folder) in bookmarks.roots.iter() {
+                insert_storage(&mut input["roots"][root], folder);
+            }
+            if let Value::Object(input) = &mut input {
+                input.insert("profile".into(), serde_json::to_value(profile)?);
+            }
+            return Ok(filter.filter_json(input)?);
+        }
+
+        // Nodes without a GUID of their own are named within the profile:
+        let scope = match &profile.iri {
+            Some(iri) => format!("{iri}#"),
+            None => "_:".into(),
+        };
+        // The profile lists its root folders, whose members nest the rest:
+        let items: Vec<Value> = bookmarks
+            .roots
+            .iter()
+            .map(|(root, folder)| folder_item(self.vocabulary, &scope, root, root, None, folder))
+            .collect();
+
+        let mut document = Map::new();
+        document.insert("@context".into(), context(self.vocabulary));
+        if let Some(iri) = &profile.iri {
+            document.insert("@id".into(), iri.as_str().into());
+        }
+        document.insert("@type".into(), "know:BrowserProfile".into());
+        if let Some(browser) = &profile.browser {
+            document.insert("browser".into(), browser.as_str().into());
+        }
+        if let Some(name) = &profile.name {
+            document.insert("label".into(), name.as_str().into());
+        }
+        if !profile.sources.is_empty() {
+            document.insert("source".into(), json!(profile.sources));
+        }
+        document.insert("items".into(), items.into());
+        Ok(document.into())
+    }
+}
+
+/// The browser profile that the top-level JSON-LD node describes.
+#[derive(Default, Serialize)]
+struct Profile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    iri: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    browser: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    sources: Vec<String>,
+}
+
+impl From<&ProfileBookmarks> for Profile {
+    fn from(profile: &ProfileBookmarks) -> Self {
+        Self {
+            iri: profile.iri(),
+            browser: profile.browser.clone(),
+            name: profile.profile.clone(),
+            sources: profile.sources.iter().map(|source| source.iri()).collect(),
+        }
+    }
+}
+
+fn context(vocabulary: Vocabulary) -> Value {
+    let (know, schema) = (vocabulary.know(), vocabulary.schema());
+    let mut context = Map::new();
+    let mut define = |term: &str, definition: Value, enabled: bool| {
+        if enabled {
+            context.insert(term.into(), definition);
+        }
+    };
+    define("know", json!("https://know.dev/"), true);
+    define("schema", json!("https://schema.org/"), schema);
+    define("rdfs", json!("http://www.w3.org/2000/01/rdf-schema#"), true);
+    define("xsd", json!("http://www.w3.org/2001/XMLSchema#"), true);
+    define(
+        "items",
+        json!({
+            "@id": "rdfs:member",
+            "@container": "@set",
+        }),
+        true,
+    );
+    define("browser", json!({ "@id": "know:browser" }), true);
+    define("label", json!({ "@id": "rdfs:label" }), true);
+    define(
+        "source",
+        json!({
+            "@id": "know:source",
+            "@type": "@id",
+            "@container": "@set",
+        }),
+        true,
+    );
+    define(
+        "created",
+        json!({
+            "@id": "know:created",
+            "@type": "xsd:dateTime",
+        }),
+        know,
+    );
+    define(
+        "modified",
+        json!({
+            "@id": "know:modified",
+            "@type": "xsd:dateTime",
+        }),
+        know,
+    );
+    define(
+        "lastUsed",
+        json!({
+            "@id": "know:lastUsed",
+            "@type": "xsd:dateTime",
+        }),
+        true,
+    );
+    define(
+        "title",
+        json!({
+            "@id": "know:title",
+            "@language": "en",
+        }),
+        know,
+    );
+    define(
+        "link",
+        json!({
+            "@id": "know:link",
+            "@type": "@id",
+        }),
+        know,
+    );
+    define("root", json!({ "@id": "know:root" }), true);
+    define("storage", json!({ "@id": "know:storage" }), true);
+    define(
+        "folder",
+        json!({
+            "@id": "know:folder",
+            "@type": "@id",
+        }),
+        know,
+    );
+    define(
+        "position",
+        json!({
+            "@id": "know:position",
+            "@type": "xsd:integer",
+        }),
+        know,
+    );
+    define(
+        "annotations",
+        json!({
+            "@id": "know:annotation",
+            "@container": "@set",
+        }),
+        true,
+    );
+    define("key", json!({ "@id": "know:key" }), true);
+    define("value", json!({ "@id": "know:value" }), true);
+    define(
+        "members",
+        json!({
+            "@id": "know:member",
+            "@type": "@id",
+            "@container": "@set",
+        }),
+        know,
+    );
+    define(
+        "name",
+        json!({
+            "@id": "schema:name",
+            "@language": "en",
+        }),
+        schema,
+    );
+    define(
+        "url",
+        json!({
+            "@id": "schema:url",
+            "@type": "@id",
+        }),
+        schema,
+    );
+    define(
+        "dateCreated",
+        json!({
+            "@id": "schema:dateCreated",
+            "@type": "xsd:dateTime",
+        }),
+        schema,
+    );
+    define(
+        "dateModified",
+        json!({
+            "@id": "schema:dateModified",
+            "@type": "xsd:dateTime",
+        }),
+        schema,
+    );
+    define(
+        "isPartOf",
+        json!({
+            "@id": "schema:isPartOf",
+            "@type": "@id",
+        }),
+        schema,
+    );
+    define(
+        "hasPart",
+        json!({
+            "@id": "schema:hasPart",
+            "@type": "@id",
+            "@container": "@set",
+        }),
+        schema,
+    );
+    context.into()
+}
+
+impl Vocabulary {
+    /// The names accepted by [`Vocabulary::from_str`].
+    pub const NAMES: &[&str] = &["know", "schema", "both"];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Vocabulary::Know => "know",
+            Vocabulary::Schema => "schema",
+            Vocabulary::Both => "both",
+        }
+    }
+
+    fn know(self) -> bool {
+        self != Vocabulary::Schema
+    }
+
+    fn schema(self) -> bool {
+        self != Vocabulary::Know
+    }
+
+    /// Returns the `@type` of a node, given its KNOW and schema.org classes.
+    fn class(self, know: &str, schema: &str) -> Value {
+        match self {
+            Vocabulary::Know => know.into(),
+            Vocabulary::Schema => schema.into(),
+            Vocabulary::Both => json!([know, schema]),
+        }
+    }
+}
+
+impl fmt::Display for Vocabulary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Vocabulary {
+    type Err = miette::Report;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Ok(match input.to_ascii_lowercase().as_str() {
+            "know" => Vocabulary::Know,
+            "schema" | "schema.org" => Vocabulary::Schema,
+            "both" => Vocabulary::Both,
+            _ => {
+                return Err(miette!(
+                    help = format!("Supported vocabularies: {}", Vocabulary::NAMES.join(", ")),
+                    "Unknown vocabulary: {}",
+                    input
+                ));
+            },
+        })
+    }
+}
+
+/// The parent folder IRI and the position within it.
+type Parent<'a> = Option<(&'a str, usize)>;
+
+fn folder_item(
+    vocabulary: Vocabulary,
+    scope: &str,
+    root: &str,
+    path: &str,
+    parent: Parent,
+    folder: &BookmarkFolder,
+) -> Value {
+    // Every profile has the same GUIDs for its root folders:
+    let guid = parent.and(folder.guid.as_deref());
+    let id = node_id(scope, path, guid);
+    let mut item = Map::new();
+    item.insert("@id".into(), id.clone().into());
+    item.insert(
+        "@type".into(),
+        vocabulary.class("know:BookmarkFolder", "schema:Collection"),
+    );
+    insert_date(
+        &mut item,
+        vocabulary,
+        ("created", "dateCreated"),
+        folder.date_added,
+    );
+    insert_date(
+        &mut item,
+        vocabulary,
+        ("modified", "dateModified"),
+        folder.date_modified,
+    );
+    insert_date(
+        &mut item,
+        vocabulary,
+        ("lastUsed", "lastUsed"),
+        folder.date_last_used,
+    );
+    insert_term(
+        &mut item,
+        vocabulary,
+        ("title", "name"),
+        folder.name.as_str(),
+    );
+    insert_location(&mut item, vocabulary, root, folder.storage, parent);
+    insert_annotations(&mut item, &folder.meta_info);
+    let members: Vec<Value> = folder
+        .children
+        .iter()
+        .enumerate()
+        .map(|(position, child)| {
+            let path = format!("{path}-{position}");
+            let parent = Some((id.as_str(), position));
+            match child {
+                BookmarkNode::Url(url) => url_item(vocabulary, scope, root, &path, parent, url),
+                BookmarkNode::Folder(folder) => {
+                    folder_item(vocabulary, scope, root, &path, parent, folder)
+                },
+            }
+        })
+        .collect();
+    insert_term(&mut item, vocabulary, ("members", "hasPart"), members);
+    item.into()
+}
+
+fn url_item(
+    vocabulary: Vocabulary,
+    scope: &str,
+    root: &str,
+    path: &str,
+    parent: Parent,
+    url: &BookmarkUrl,
+) -> Value {
+    let mut item = Map::new();
+    let id = node_id(scope, path, url.guid.as_deref());
+    item.insert("@id".into(), id.into());
+    item.insert(
+        "@type".into(),
+        vocabulary.class("know:Bookmark", "schema:WebPage"),
+    );
+    insert_date(
+        &mut item,
+        vocabulary,
+        ("created", "dateCreated"),
+        url.date_added,
+    );
+    insert_date(
+        &mut item,
+        vocabulary,
+        ("lastUsed", "lastUsed"),
+        url.date_last_used,
+    );
+    insert_term(&mut item, vocabulary, ("title", "name"), url.name.as_str());
+    insert_term(&mut item, vocabulary, ("link", "url"), url.url.as_str());
+    insert_location(&mut item, vocabulary, root, url.storage, parent);
+    insert_annotations(&mut item, &url.meta_info);
+    item.into()
+}
+
+/// Returns the IRI of a node: `urn:uuid:` and its GUID, if it has one, or
+/// else its path within the profile, such as `bookmark_bar-0-2`, appended to
+/// the scope (the profile IRI and `#`, or `_:` for a blank node).
+fn node_id(scope: &str, path: &str, guid: Option<&str>) -> String {
+    match guid.filter(|guid| !guid.is_empty()) {
+        Some(guid) => format!("urn:uuid:{guid}"),
+        None => format!("{scope}{path}"),
+    }
+}
+
+/// Inserts a property under its KNOW term, its schema.org term, or both, as
+/// given by the vocabulary. Properties without a schema.org equivalent have
+/// the same term in both places.
+fn insert_term(
+    item: &mut Map<String, Value>,
+    vocabulary: Vocabulary,
+    (know, schema): (&str, &str),
+    value: impl Into<Value>,
+) {
+    let value = value.into();
+    if vocabulary.know() || know == schema {
+        item.insert(know.into(), value.clone());
+    }
+    if vocabulary.schema() && know != schema {
+        item.insert(schema.into(), value);
+    }
+}
+
+fn insert_date(
+    item: &mut Map<String, Value>,
+    vocabulary: Vocabulary,
+    terms: (&str, &str),
+    timestamp: Option<i64>,
+) {
+    if let Some(date) = timestamp.and_then(format_webkit_time) {
+        insert_term(item, vocabulary, terms, date);
+    }
+}
+
+fn insert_location(
+    item: &mut Map<String, Value>,
+    vocabulary: Vocabulary,
+    root: &str,
+    storage: Option<BookmarkStorage>,
+    parent: Parent,
+) {
+    item.insert("root".into(), root.into());
+    if let Some(storage) = storage {
+        item.insert("storage".into(), json!(storage));
+    }
+    if let Some((folder, position)) = parent {
+        insert_term(item, vocabulary, ("folder", "isPartOf"), folder);
+        insert_term(item, vocabulary, ("position", "schema:position"), position);
+    }
+}
+
+/// Adds the storage of a folder and its descendants to their serialized
+/// form for the jq filter, as Chromium's file format has no such key.
+#[cfg(feature = "jq")]
+fn insert_storage(value: &mut Value, folder: &BookmarkFolder) {
+    if let Some(storage) = folder.storage {
+        value["storage"] = json!(storage);
+    }
+    let Some(children) = value.get_mut("children").and_then(Value::as_array_mut) else {
+        return;
+    };
+    for (value, child) in children.iter_mut().zip(&folder.children) {
+        match child {
+            BookmarkNode::Url(url) => {
+                if let Some(storage) = url.storage {
+                    value["storage"] = json!(storage);
+                }
+            },
+            BookmarkNode::Folder(folder) => insert_storage(value, folder),
+        }
+    }
+}
+
+fn insert_annotations(item: &mut Map<String, Value>, meta_info: &BTreeMap<String, String>) {
+    if meta_info.is_empty() {
+        return;
+    }
+    let annotations = meta_info
+        .iter()
+        .map(|(key, value)| {
+            json!({
+                "@type": "know:Annotation",
+                "key": key,
+                "value": value,
+            })
         })
+        .collect();
+    item.insert("annotations".into(), annotations);
+}
+
+impl fmt::Display for TransformError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TransformError::Json(error) => write!(f, "invalid bookmarks JSON: {error}"),
+            #[cfg(feature = "jq")]
+            TransformError::Filter(error) => write!(f, "jq filter failed: {error}"),
+        }
+    }
+}
+
+impl core::error::Error for TransformError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            TransformError::Json(error) => Some(error),
+            #[cfg(feature = "jq")]
+            TransformError::Filter(error) => Some(error),
+        }
     }
+}
+
+impl From<serde_json::Error> for TransformError {
+    fn from(error: serde_json::Error) -> Self {
+        TransformError::Json(error)
+    }
+}
 
-    pub fn execute(&self, input: Value) -> Result<Value, JsonFilterError> {
-        self.filter.filter_json(input)
+#[cfg(feature = "jq")]
+impl From<JsonFilterError> for TransformError {
+    fn from(error: JsonFilterError) -> Self {
+        TransformError::Filter(error)
     }
 }
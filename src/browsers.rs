@@ -6,7 +6,7 @@ use std::string::{String, ToString};
 use std::vec::Vec;
 use std::{format, vec};
 
-use crate::specialized;
+use crate::{BookmarkStorage, BookmarksFile, ChecksumStatus, specialized};
 
 /// Configuration for browser-specific user data paths.
 #[derive(Clone, Copy)]
@@ -115,6 +115,18 @@ impl BrowserConfig {
         }
     }
 
+    pub fn account_bookmarks_path(&self, profile_name: Option<&str>) -> Result<PathBuf> {
+        match self.browser_type() {
+            Some(Browser::Arc) => Err(miette!(
+                "Browser '{}' does not keep account bookmarks",
+                self.name
+            )),
+            _ => self
+                .profile_path(profile_name)
+                .map(|path| path.join("AccountBookmarks")),
+        }
+    }
+
     pub fn list_profiles(&self) -> Result<Vec<String>> {
         match self.browser_type() {
             Some(Browser::Arc) => {
@@ -127,13 +139,13 @@ impl BrowserConfig {
                 if base_path.is_dir() {
                     for entry in std::fs::read_dir(&base_path).into_diagnostic()? {
                         let entry = entry.into_diagnostic()?;
-                        if entry.file_type().into_diagnostic()?.is_dir() {
-                            if let Some(name) = entry.file_name().to_str() {
-                                // Arc profiles are typically named like "Profile 1", "Profile 2", etc.
-                                // or "Default" for the default profile
-                                if matches!(name, "Default") || name.starts_with("Profile ") {
-                                    profiles.push(name.to_string());
-                                }
+                        if entry.file_type().into_diagnostic()?.is_dir()
+                            && let Some(name) = entry.file_name().to_str()
+                        {
+                            // Arc profiles are typically named like "Profile 1", "Profile 2", etc.
+                            // or "Default" for the default profile
+                            if matches!(name, "Default") || name.starts_with("Profile ") {
+                                profiles.push(name.to_string());
                             }
                         }
                     }
@@ -155,12 +167,11 @@ impl BrowserConfig {
                 let mut profiles = Vec::new();
                 for entry in std::fs::read_dir(base_path).into_diagnostic()? {
                     let entry = entry.into_diagnostic()?;
-                    if entry.file_type().into_diagnostic()?.is_dir() {
-                        if let Some(name) = entry.file_name().to_str() {
-                            if matches!(name, "Default") || name.starts_with("Profile ") {
-                                profiles.push(name.to_string());
-                            }
-                        }
+                    if entry.file_type().into_diagnostic()?.is_dir()
+                        && let Some(name) = entry.file_name().to_str()
+                        && (matches!(name, "Default") || name.starts_with("Profile "))
+                    {
+                        profiles.push(name.to_string());
                     }
                 }
                 Ok(profiles)
@@ -170,7 +181,7 @@ impl BrowserConfig {
 }
 
 /// Converts Arc's StorableSidebar.json format to standard Chromium bookmarks format
-fn convert_arc_to_bookmarks(arc_data: Value, profile: Option<&str>) -> Result<Value> {
+fn convert_arc_to_bookmarks(arc_data: Value, profile: Option<&str>) -> Result<BookmarksFile> {
     specialized::arc::convert_arc_bookmarks_to_chromium(arc_data, profile)
 }
 
@@ -216,7 +227,107 @@ pub fn get_browser_from_url(url: &str) -> Option<BrowserConfig> {
         .map(|(name, paths)| BrowserConfig { name, paths })
 }
 
-pub fn fetch_bookmarks(url: &str) -> Result<Vec<Value>> {
+/// Bookmarks read from a browser profile.
+#[derive(Clone, Debug)]
+pub struct ProfileBookmarks {
+    /// The browser name (e.g., `chrome`), if known.
+    pub browser: Option<String>,
+    /// The profile name (e.g., `Default`), if known.
+    pub profile: Option<String>,
+    /// The files the bookmarks were read from, local storage first.
+    pub sources: Vec<BookmarksSource>,
+    /// The bookmarks from all sources, merged.
+    pub bookmarks: BookmarksFile,
+    /// Problems that did not prevent reading the profile, such as an
+    /// unreadable `AccountBookmarks` file.
+    pub warnings: Vec<String>,
+}
+
+impl From<BookmarksFile> for ProfileBookmarks {
+    /// Wraps bookmarks of unknown origin, such as those read from stdin.
+    fn from(bookmarks: BookmarksFile) -> Self {
+        Self {
+            browser: None,
+            profile: None,
+            sources: Vec::new(),
+            bookmarks,
+            warnings: Vec::new(),
+        }
+    }
+}
+
+impl ProfileBookmarks {
+    /// The IRI identifying the browser profile, such as
+    /// `chrome://bookmarks/Profile%201`, if the browser and profile are known.
+    pub fn iri(&self) -> Option<String> {
+        let paths = SUPPORTED_BROWSERS.get(self.browser.as_deref()?)?;
+        let profile = self.profile.as_deref()?;
+        let mut iri = format!("{}/", paths.url_prefix);
+        push_percent_encoded(&mut iri, profile, b"");
+        Some(iri)
+    }
+}
+
+/// A bookmarks file read from a browser profile.
+#[derive(Clone, Debug)]
+pub struct BookmarksSource {
+    /// The file the bookmarks were actually read from.
+    pub path: PathBuf,
+    /// Whether this is the local or the account bookmarks file.
+    pub storage: BookmarkStorage,
+    /// The result of verifying the file's stored checksum.
+    pub checksum: ChecksumStatus,
+    /// Why the primary file was skipped, if the backup file was used instead.
+    pub fallback_reason: Option<String>,
+}
+
+impl BookmarksSource {
+    /// The `file:` IRI of the file, such as
+    /// `file:///home/user/.config/google-chrome/Profile%201/Bookmarks`.
+    pub fn iri(&self) -> String {
+        // Windows paths such as `C:\Users` become `/C:/Users`:
+        let path = self.path.to_string_lossy().replace('\\', "/");
+        let mut iri = String::from("file://");
+        if !path.starts_with('/') {
+            iri.push('/');
+        }
+        push_percent_encoded(&mut iri, &path, b"/:");
+        iri
+    }
+}
+
+/// Appends text to an IRI, percent-encoding all but the unreserved
+/// characters and the given delimiters.
+fn push_percent_encoded(iri: &mut String, text: &str, delimiters: &[u8]) {
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                iri.push(byte as char)
+            },
+            byte if delimiters.contains(&byte) => iri.push(byte as char),
+            _ => iri.push_str(&format!("%{byte:02X}")),
+        }
+    }
+}
+
+/// Options for [`fetch_bookmarks_with`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FetchOptions {
+    /// Read the `Bookmarks.bak` backup file instead of `Bookmarks`.
+    pub backup: bool,
+}
+
+pub fn fetch_bookmarks(url: &str) -> Result<Vec<Result<ProfileBookmarks>>> {
+    fetch_bookmarks_with(url, &FetchOptions::default())
+}
+
+/// Reads the bookmarks of the profile named by the URL, or of every profile
+/// of the browser. Each profile has its own result, so that one unreadable
+/// profile does not hide the others.
+pub fn fetch_bookmarks_with(
+    url: &str,
+    options: &FetchOptions,
+) -> Result<Vec<Result<ProfileBookmarks>>> {
     let browser = get_browser_from_url(url).ok_or_else(|| {
         miette!(
             "Unsupported URL: {}. Supported prefixes: {:?}",
@@ -265,16 +376,11 @@ pub fn fetch_bookmarks(url: &str) -> Result<Vec<Value>> {
             "Default".to_string()
         };
 
-        if let Ok(path) = browser.bookmarks_path(Some(profile_to_use.as_str())) {
-            if let Ok(bookmarks) = read_bookmarks_file(&path, Some(profile_to_use.as_str())) {
-                return Ok(vec![bookmarks]);
-            }
-        }
-
-        return Err(miette!(
-            "No valid bookmarks files found for browser: {}",
-            browser.name()
-        ));
+        return Ok(vec![read_profile_bookmarks(
+            &browser,
+            &profile_to_use,
+            options,
+        )]);
     }
 
     // Other browsers
@@ -288,27 +394,121 @@ pub fn fetch_bookmarks(url: &str) -> Result<Vec<Value>> {
         return Err(miette!("No profiles found for browser: {}", browser.name()));
     }
 
-    let mut outputs = Vec::new();
+    Ok(profiles
+        .iter()
+        .map(|profile| read_profile_bookmarks(&browser, profile, options))
+        .collect())
+}
 
-    for profile in profiles {
-        if let Ok(path) = browser.bookmarks_path(Some(&profile)) {
-            if let Ok(bookmarks) = read_bookmarks_file(&path, Some(&profile)) {
-                outputs.push(bookmarks);
-            }
+/// Reads a profile's local bookmarks, merged with its account bookmarks
+/// if Chrome keeps a separate `AccountBookmarks` file.
+fn read_profile_bookmarks(
+    browser: &BrowserConfig,
+    profile: &str,
+    options: &FetchOptions,
+) -> Result<ProfileBookmarks> {
+    let path = browser.bookmarks_path(Some(profile))?;
+    let (source, mut bookmarks) =
+        read_bookmarks_source(&path, profile, BookmarkStorage::Local, options)
+            .wrap_err_with(|| format!("No valid bookmarks file found for profile {profile}"))?;
+    let mut sources = vec![source];
+    let mut warnings = Vec::new();
+
+    // The local bookmarks are still usable if the account bookmarks are not:
+    if let Ok(path) = browser.account_bookmarks_path(Some(profile))
+        && (path.is_file() || path.with_extension("bak").is_file())
+    {
+        // Chrome may not have backed up the account bookmarks yet:
+        let mut options = *options;
+        if options.backup && !path.with_extension("bak").is_file() {
+            warnings.push(format!(
+                "No backup of the account bookmarks of profile {profile}; using {} instead",
+                path.display()
+            ));
+            options.backup = false;
+        }
+        match read_bookmarks_source(&path, profile, BookmarkStorage::Account, &options) {
+            Ok((source, account_bookmarks)) => {
+                sources.push(source);
+                bookmarks.merge(account_bookmarks);
+            },
+            Err(error) => {
+                let reasons: Vec<String> = error.chain().map(|cause| cause.to_string()).collect();
+                warnings.push(format!(
+                    "Skipped the account bookmarks of profile {profile}: {}",
+                    reasons.join(": ")
+                ));
+            },
         }
     }
 
-    if outputs.is_empty() {
-        return Err(miette!(
-            "No valid bookmarks files found for browser: {}",
-            browser.name()
-        ));
-    }
+    Ok(ProfileBookmarks {
+        browser: Some(browser.name().to_string()),
+        profile: Some(profile.to_string()),
+        sources,
+        bookmarks,
+        warnings,
+    })
+}
 
-    Ok(outputs)
+/// Reads a bookmarks file, falling back to its `.bak` backup if the primary
+/// file is missing or cannot be parsed, or if its checksum does not match
+/// while the backup's does. The profile name is used for Arc's sidebar file.
+pub fn read_bookmarks_source(
+    primary_path: &Path,
+    profile: &str,
+    storage: BookmarkStorage,
+    options: &FetchOptions,
+) -> Result<(BookmarksSource, BookmarksFile)> {
+    let backup_path = primary_path.with_extension("bak");
+
+    let (path, mut bookmarks, fallback_reason) = if options.backup {
+        let bookmarks = read_bookmarks_file(&backup_path, Some(profile))?;
+        (backup_path, bookmarks, None)
+    } else {
+        match read_bookmarks_file(primary_path, Some(profile)) {
+            Ok(bookmarks) => {
+                // A file that fails verification may be corrupted, unlike an
+                // intact backup:
+                let mismatch =
+                    matches!(bookmarks.checksum_status(), ChecksumStatus::Mismatch { .. });
+                let backup = mismatch
+                    .then(|| read_bookmarks_file(&backup_path, Some(profile)).ok())
+                    .flatten()
+                    .filter(|backup| backup.checksum_status() == ChecksumStatus::Valid);
+                match backup {
+                    Some(backup) => {
+                        let reason =
+                            format!("Bookmarks checksum mismatch in {}", primary_path.display());
+                        (backup_path, backup, Some(reason))
+                    },
+                    None => (primary_path.to_path_buf(), bookmarks, None),
+                }
+            },
+            Err(error) => match read_bookmarks_file(&backup_path, Some(profile)) {
+                Ok(bookmarks) => (backup_path, bookmarks, Some(error.to_string())),
+                Err(backup_error) => {
+                    return Err(error.wrap_err(format!(
+                        "The backup file could not be used either: {backup_error}"
+                    )));
+                },
+            },
+        }
+    };
+
+    let checksum = bookmarks.checksum_status();
+    bookmarks.set_storage(storage);
+
+    let source = BookmarksSource {
+        path,
+        storage,
+        checksum,
+        fallback_reason,
+    };
+    Ok((source, bookmarks))
 }
 
-fn read_bookmarks_file(path: &Path, profile: Option<&str>) -> Result<Value> {
+fn read_bookmarks_file(path: &Path, profile: Option<&str>) -> Result<BookmarksFile> {
     if !path.is_file() {
         return Err(miette!("Bookmarks file not found at {}", path.display()));
     }
@@ -3,7 +3,15 @@
 #[cfg(not(feature = "std"))]
 compile_error!("asimov-chromium-cataloger requires the 'std' feature");
 
-use asimov_chromium_module::browsers;
+#[cfg(feature = "sqlite")]
+use asimov_chromium_module::formats::sqlite;
+#[cfg(feature = "jq")]
+use asimov_chromium_module::jq;
+use asimov_chromium_module::{
+    BookmarksTransform, ChecksumStatus, Vocabulary,
+    browsers::{self, FetchOptions},
+    formats::{Exporter, OutputFormat, markdown},
+};
 use asimov_module::SysexitsError::{self, *};
 use clap::Parser;
 use clientele::StandardOptions;
@@ -12,7 +20,8 @@ use dogma::{
     UriScheme::{Chrome, Other},
     UriValueParser,
 };
-use std::error::Error;
+use miette::{Severity, miette};
+use std::{error::Error, path::PathBuf};
 
 /// asimov-chromium-cataloger
 #[derive(Debug, Parser)]
@@ -21,10 +30,47 @@ struct Options {
     #[clap(flatten)]
     flags: StandardOptions,
 
-    /// The output format.
+    /// The output format (`jsonld`, `json`, `jsonl`, `turtle`, `ntriples`,
+    /// `trig`, `nquads`, `html`, `csv`, `tsv`, `markdown`, `opml`, `atom`,
+    /// `firefox`, `pinboard`, `dot`, or `graphml`).
     #[arg(value_name = "FORMAT", short = 'o', long)]
     output: Option<String>,
 
+    /// The number of most recently added bookmarks in the `atom` feed
+    /// (default: 50).
+    #[arg(value_name = "N", long)]
+    limit: Option<usize>,
+
+    /// Include a node for every bookmark in the `dot` and `graphml` graphs.
+    #[arg(long)]
+    leaves: bool,
+
+    /// Write an Obsidian vault of Markdown notes into this directory instead
+    /// of printing the output.
+    #[arg(value_name = "DIR", long)]
+    vault: Option<PathBuf>,
+
+    /// Write the bookmarks into this SQLite database instead of printing the
+    /// output, updating any rows from earlier runs.
+    #[cfg(feature = "sqlite")]
+    #[arg(value_name = "FILE", long)]
+    sqlite: Option<PathBuf>,
+
+    /// The vocabulary of the JSON-LD and RDF output (`know`, `schema`, or
+    /// `both`).
+    #[arg(value_name = "VOCABULARY", long)]
+    vocabulary: Option<String>,
+
+    /// A jq filter to run instead of the default transform: the name of a
+    /// built-in filter (`bookmarks`, `flatten`) or the path to a jq program.
+    #[cfg(feature = "jq")]
+    #[arg(value_name = "FILTER", long)]
+    filter: Option<String>,
+
+    /// Read the `Bookmarks.bak` backup file instead of `Bookmarks`.
+    #[arg(long)]
+    backup: bool,
+
     /// The browser bookmarks URL to catalog (e.g., `chrome://bookmarks`, `brave://bookmarks/2`)
     #[arg(value_name = "URL", value_parser = UriValueParser::new(&[
         Chrome,
@@ -64,6 +110,60 @@ pub fn main() -> Result<SysexitsError, Box<dyn Error>> {
     #[cfg(feature = "tracing")]
     asimov_module::init_tracing_subscriber(&options.flags).expect("failed to initialize logging");
 
+    // Parse the output format:
+    let format: OutputFormat = match options.output.as_deref().map(str::parse).transpose() {
+        Ok(format) => format.unwrap_or_default(),
+        Err(error) => {
+            eprintln!("{:?}", error);
+            return Ok(EX_USAGE);
+        },
+    };
+    let vocabulary: Vocabulary = match options.vocabulary.as_deref().map(str::parse).transpose() {
+        Ok(vocabulary) => vocabulary.unwrap_or_default(),
+        Err(error) => {
+            eprintln!("{:?}", error);
+            return Ok(EX_USAGE);
+        },
+    };
+    if options.vocabulary.is_some() && !format.uses_transform() {
+        eprintln!(
+            "{:?}",
+            miette!("The --vocabulary option requires a JSON-LD or RDF output format")
+        );
+        return Ok(EX_USAGE);
+    }
+    #[cfg(feature = "jq")]
+    if options.vocabulary.is_some() && options.filter.is_some() {
+        eprintln!(
+            "{:?}",
+            miette!("The --vocabulary option cannot be combined with --filter")
+        );
+        return Ok(EX_USAGE);
+    }
+    #[cfg(feature = "jq")]
+    if options.filter.is_some() && !format.uses_transform() {
+        eprintln!(
+            "{:?}",
+            miette!("The --filter option requires a JSON-LD or RDF output format")
+        );
+        return Ok(EX_USAGE);
+    }
+    if options.vault.is_some() && options.output.is_some() && format != OutputFormat::Markdown {
+        eprintln!(
+            "{:?}",
+            miette!("The --vault option requires the markdown output format")
+        );
+        return Ok(EX_USAGE);
+    }
+    #[cfg(feature = "sqlite")]
+    if options.sqlite.is_some() && (options.output.is_some() || options.vault.is_some()) {
+        eprintln!(
+            "{:?}",
+            miette!("The --sqlite option cannot be combined with --output or --vault")
+        );
+        return Ok(EX_USAGE);
+    }
+
     // Parse the input JSON:
     let input_url = &options.url;
     // let outputs: Vec<Value> = if input_url.starts_with("-") {
@@ -73,15 +173,94 @@ pub fn main() -> Result<SysexitsError, Box<dyn Error>> {
     // } else {
     //     browsers::fetch_bookmarks(input_url)?
     // };
-    let outputs = browsers::fetch_bookmarks(input_url.to_string().as_ref())?;
-
-    // Transform JSON to JSON-LD:
-    let transform = asimov_chromium_module::BookmarksTransform::new()?;
-    for input in outputs {
-        let output = transform.execute(input)?;
-        // Serialize the output JSON-LD:
-        println!("{}", output);
+    let fetch_options = FetchOptions {
+        backup: options.backup,
+    };
+    let mut outputs = Vec::new();
+    for result in browsers::fetch_bookmarks_with(input_url.to_string().as_ref(), &fetch_options)? {
+        match result {
+            Ok(profile) => outputs.push(profile),
+            // Report profiles whose bookmarks could not be read:
+            Err(error) => {
+                let reasons: Vec<String> = error.chain().map(|cause| cause.to_string()).collect();
+                eprintln!(
+                    "{:?}",
+                    miette!(severity = Severity::Warning, "{}", reasons.join(": "))
+                );
+            },
+        }
+    }
+    if outputs.is_empty() {
+        eprintln!(
+            "{:?}",
+            miette!("No valid bookmarks files found at {input_url}")
+        );
+        return Ok(EX_NOINPUT);
+    }
+
+    // Prepare the JSON-LD transform:
+    #[cfg(feature = "jq")]
+    let transform = match &options.filter {
+        Some(filter) => BookmarksTransform::with_jq(&jq::resolve(filter)?)?,
+        None => BookmarksTransform::new()?.with_vocabulary(vocabulary),
+    };
+    #[cfg(not(feature = "jq"))]
+    let transform = BookmarksTransform::new()?.with_vocabulary(vocabulary);
+    for input in &outputs {
+        // Report problems that did not prevent reading the profile:
+        for warning in &input.warnings {
+            eprintln!("{:?}", miette!(severity = Severity::Warning, "{warning}"));
+        }
+
+        for source in &input.sources {
+            // Report when the backup file had to be used instead:
+            if let Some(reason) = &source.fallback_reason {
+                eprintln!(
+                    "{:?}",
+                    miette!(
+                        severity = Severity::Warning,
+                        "{reason}; using backup file {} instead",
+                        source.path.display()
+                    )
+                );
+            }
+
+            // Report bookmarks files that fail checksum verification:
+            if let ChecksumStatus::Mismatch { expected, actual } = &source.checksum {
+                eprintln!(
+                    "{:?}",
+                    miette!(
+                        severity = Severity::Warning,
+                        "Bookmarks checksum mismatch in {} (expected {expected}, computed {actual}): the file may be corrupted or hand-edited",
+                        source.path.display()
+                    )
+                );
+            }
+        }
+    }
+
+    // Write the Obsidian vault, if requested:
+    if let Some(directory) = &options.vault {
+        markdown::write_vault(&outputs, directory)?;
+        return Ok(EX_OK);
+    }
+
+    // Write the SQLite catalog, if requested:
+    #[cfg(feature = "sqlite")]
+    if let Some(path) = &options.sqlite {
+        sqlite::write_catalog(&outputs, path)?;
+        return Ok(EX_OK);
+    }
+
+    // Serialize the output:
+    let mut exporter = Exporter::new(format).with_transform(transform);
+    if let Some(limit) = options.limit {
+        exporter = exporter.with_limit(limit);
+    }
+    if options.leaves {
+        exporter = exporter.with_leaves(true);
     }
+    print!("{}", exporter.export(&outputs)?);
 
     Ok(EX_OK)
 }
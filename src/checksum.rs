@@ -0,0 +1,80 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::{BookmarkFolder, BookmarkNode, BookmarksFile};
+use md5::{Digest, Md5};
+use std::format;
+use std::string::String;
+
+/// The result of verifying the `checksum` of a Chromium `Bookmarks` file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChecksumStatus {
+    /// The stored checksum matches the file contents.
+    Valid,
+    /// The file has no stored checksum (e.g., converted from another format).
+    Missing,
+    /// The stored checksum does not match the file contents.
+    Mismatch { expected: String, actual: String },
+}
+
+impl BookmarksFile {
+    /// Computes the checksum Chromium would store for this file.
+    ///
+    /// This is an MD5 digest over the id, title, type and URL of every node
+    /// under the bookmark bar, other and mobile roots, in document order.
+    pub fn compute_checksum(&self) -> String {
+        let mut hasher = Md5::new();
+        let roots = [
+            &self.roots.bookmark_bar,
+            &self.roots.other,
+            &self.roots.synced,
+        ];
+        for root in roots.into_iter().flatten() {
+            update_folder(&mut hasher, root);
+        }
+        hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    /// Verifies the stored checksum against the file contents.
+    pub fn checksum_status(&self) -> ChecksumStatus {
+        let Some(expected) = &self.checksum else {
+            return ChecksumStatus::Missing;
+        };
+        let actual = self.compute_checksum();
+        if expected.eq_ignore_ascii_case(&actual) {
+            ChecksumStatus::Valid
+        } else {
+            ChecksumStatus::Mismatch {
+                expected: expected.clone(),
+                actual,
+            }
+        }
+    }
+}
+
+fn update_folder(hasher: &mut Md5, folder: &BookmarkFolder) {
+    hasher.update(folder.id.as_deref().unwrap_or_default());
+    update_title(hasher, &folder.name);
+    hasher.update("folder");
+    for child in &folder.children {
+        match child {
+            BookmarkNode::Url(url) => {
+                hasher.update(url.id.as_deref().unwrap_or_default());
+                update_title(hasher, &url.name);
+                hasher.update("url");
+                hasher.update(&url.url);
+            },
+            BookmarkNode::Folder(folder) => update_folder(hasher, folder),
+        }
+    }
+}
+
+/// Chromium hashes titles as their in-memory UTF-16 (little-endian) code units.
+fn update_title(hasher: &mut Md5, title: &str) {
+    for unit in title.encode_utf16() {
+        hasher.update(unit.to_le_bytes());
+    }
+}
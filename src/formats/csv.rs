@@ -0,0 +1,75 @@
+// This is free and unencumbered software released into the public domain.
+
+//! CSV and TSV export, with one row per bookmark.
+//!
+//! Fields are quoted as specified by RFC 4180, and rows end with CRLF.
+
+use crate::browsers::ProfileBookmarks;
+use crate::format_webkit_time;
+use std::string::String;
+
+/// The header row.
+pub const COLUMNS: &[&str] = &[
+    "browser",
+    "profile",
+    "root",
+    "folder",
+    "title",
+    "url",
+    "created",
+    "last_used",
+    "guid",
+];
+
+/// Renders the bookmarks of one or more profiles as comma-separated values.
+pub fn to_csv(profiles: &[ProfileBookmarks]) -> String {
+    to_table(profiles, ',')
+}
+
+/// Renders the bookmarks of one or more profiles as tab-separated values.
+pub fn to_tsv(profiles: &[ProfileBookmarks]) -> String {
+    to_table(profiles, '\t')
+}
+
+/// Renders the bookmarks of one or more profiles as a table with the given
+/// field delimiter. The folder path is the `/`-separated folder names below
+/// the root, and dates are ISO 8601.
+pub fn to_table(profiles: &[ProfileBookmarks], delimiter: char) -> String {
+    let mut output = String::new();
+    write_row(&mut output, delimiter, COLUMNS.iter().copied());
+    for profile in profiles {
+        super::for_each_url(&profile.bookmarks, |root, folders, url| {
+            let created = url.date_added.and_then(format_webkit_time);
+            let last_used = url.date_last_used.and_then(format_webkit_time);
+            let row = [
+                profile.browser.as_deref().unwrap_or_default(),
+                profile.profile.as_deref().unwrap_or_default(),
+                root,
+                &folders.join("/"),
+                &url.name,
+                &url.url,
+                created.as_deref().unwrap_or_default(),
+                last_used.as_deref().unwrap_or_default(),
+                url.guid.as_deref().unwrap_or_default(),
+            ];
+            write_row(&mut output, delimiter, row.into_iter());
+        });
+    }
+    output
+}
+
+fn write_row<'a>(output: &mut String, delimiter: char, fields: impl Iterator<Item = &'a str>) {
+    for (index, field) in fields.enumerate() {
+        if index > 0 {
+            output.push(delimiter);
+        }
+        if field.contains([delimiter, '"', '\r', '\n']) {
+            output.push('"');
+            output.push_str(&field.replace('"', "\"\""));
+            output.push('"');
+        } else {
+            output.push_str(field);
+        }
+    }
+    output.push_str("\r\n");
+}
@@ -0,0 +1,220 @@
+// This is free and unencumbered software released into the public domain.
+
+//! Firefox bookmark backup export, for use with "Restore from JSON".
+//!
+//! The bookmark bar, other and mobile roots become Firefox's toolbar, unfiled
+//! and mobile roots; roots added by Chromium forks become folders in the
+//! unfiled root. Dates are in microseconds since the Unix epoch.
+
+use super::profile_label;
+use crate::browsers::ProfileBookmarks;
+use crate::{BookmarkFolder, BookmarkNode, BookmarkUrl, webkit_to_unix_micros};
+use md5::{Digest, Md5};
+use miette::{IntoDiagnostic, Result};
+use serde::Serialize;
+use std::string::String;
+use std::vec::Vec;
+
+/// The Firefox roots in backup order, as (GUID, title, root name).
+const ROOTS: [(&str, &str, &str); 5] = [
+    ("menu________", "menu", "bookmarksMenuFolder"),
+    ("toolbar_____", "toolbar", "toolbarFolder"),
+    ("tags________", "tags", "tagsFolder"),
+    ("unfiled_____", "unfiled", "unfiledBookmarksFolder"),
+    ("mobile______", "mobile", "mobileFolder"),
+];
+
+const CONTAINER: &str = "text/x-moz-place-container";
+const PLACE: &str = "text/x-moz-place";
+
+/// Renders the bookmarks of one or more profiles as a Firefox bookmark
+/// backup. With several profiles, each profile's bookmarks are put in a
+/// folder named after the profile within each Firefox root.
+pub fn to_firefox_json(profiles: &[ProfileBookmarks]) -> Result<String> {
+    let mut backup = Backup { next_id: 1 };
+    let mut places = backup.container("", None, None);
+    places.guid = Some("root________".into());
+    places.root = Some("placesRoot");
+    let roots = ROOTS
+        .iter()
+        .map(|&(guid, title, root)| backup.root(profiles, guid, title, root))
+        .collect();
+    places.set_children(roots);
+    serde_json::to_string(&places).into_diagnostic()
+}
+
+/// Maps a Chromium root to the title of the Firefox root it goes into.
+fn firefox_root(root: &str) -> &'static str {
+    match root {
+        "bookmark_bar" => "toolbar",
+        "synced" => "mobile",
+        _ => "unfiled",
+    }
+}
+
+/// A bookmark, folder or root of the backup, as Firefox serializes it.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Item {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    guid: Option<String>,
+    title: String,
+    index: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    date_added: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_modified: Option<i64>,
+    id: u64,
+    type_code: u8,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    /// The name of a Firefox root.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    root: Option<&'static str>,
+    /// The URL of a bookmark.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    uri: Option<String>,
+    /// The contents of a folder or root.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    children: Option<Vec<Item>>,
+}
+
+impl Item {
+    /// Sets the contents of a folder, numbering them in order.
+    fn set_children(&mut self, mut children: Vec<Item>) {
+        for (index, child) in children.iter_mut().enumerate() {
+            child.index = index;
+        }
+        self.children = Some(children);
+    }
+}
+
+struct Backup {
+    next_id: u64,
+}
+
+impl Backup {
+    fn next_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    /// Creates an empty folder, to which the caller adds the contents.
+    fn container(&mut self, title: &str, added: Option<i64>, modified: Option<i64>) -> Item {
+        Item {
+            guid: None,
+            title: title.into(),
+            index: 0,
+            date_added: added.and_then(webkit_to_unix_micros),
+            last_modified: modified.and_then(webkit_to_unix_micros),
+            id: self.next_id(),
+            type_code: 2,
+            kind: CONTAINER,
+            root: None,
+            uri: None,
+            children: None,
+        }
+    }
+
+    fn root(
+        &mut self,
+        profiles: &[ProfileBookmarks],
+        guid: &str,
+        title: &str,
+        root: &'static str,
+    ) -> Item {
+        let mut item = self.container(title, None, None);
+        item.guid = Some(guid.into());
+        item.root = Some(root);
+
+        let mut contents: Vec<(&ProfileBookmarks, Vec<Item>)> = Vec::new();
+        for profile in profiles {
+            let mut children = Vec::new();
+            for (key, folder) in profile.bookmarks.roots.iter() {
+                if firefox_root(key) != title {
+                    continue;
+                }
+                match key {
+                    "bookmark_bar" | "other" | "synced" => {
+                        if profiles.len() == 1 {
+                            item.date_added = folder.date_added.and_then(webkit_to_unix_micros);
+                            item.last_modified =
+                                folder.date_modified.and_then(webkit_to_unix_micros);
+                        }
+                        children.extend(folder.children.iter().map(|node| self.node(node)));
+                    },
+                    // The permanent roots of Chromium forks become folders:
+                    _ => children.push(self.folder(folder)),
+                }
+            }
+            if !children.is_empty() {
+                contents.push((profile, children));
+            }
+        }
+
+        let children = match profiles.len() {
+            1 => contents
+                .into_iter()
+                .flat_map(|(_, children)| children)
+                .collect(),
+            // With several profiles, each gets its own folder:
+            _ => contents
+                .into_iter()
+                .map(|(profile, children)| {
+                    let mut folder = self.container(&profile_label(profile), None, None);
+                    folder.set_children(children);
+                    folder
+                })
+                .collect(),
+        };
+        item.set_children(children);
+        item
+    }
+
+    fn node(&mut self, node: &BookmarkNode) -> Item {
+        match node {
+            BookmarkNode::Url(url) => self.url(url),
+            BookmarkNode::Folder(folder) => self.folder(folder),
+        }
+    }
+
+    fn url(&mut self, url: &BookmarkUrl) -> Item {
+        Item {
+            guid: url.guid.as_deref().map(firefox_guid),
+            title: url.name.clone(),
+            index: 0,
+            date_added: url.date_added.and_then(webkit_to_unix_micros),
+            last_modified: url.date_added.and_then(webkit_to_unix_micros),
+            id: self.next_id(),
+            type_code: 1,
+            kind: PLACE,
+            root: None,
+            uri: Some(url.url.clone()),
+            children: None,
+        }
+    }
+
+    fn folder(&mut self, folder: &BookmarkFolder) -> Item {
+        let mut item = self.container(&folder.name, folder.date_added, folder.date_modified);
+        item.guid = folder.guid.as_deref().map(firefox_guid);
+        let children = folder.children.iter().map(|node| self.node(node)).collect();
+        item.set_children(children);
+        item
+    }
+}
+
+/// Derives a Firefox GUID (12 URL-safe Base64 characters) from a Chromium
+/// GUID, so that repeated exports keep the same GUIDs.
+fn firefox_guid(guid: &str) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+    let digest: [u8; 16] = Md5::digest(guid.to_lowercase()).into();
+    let mut output = String::with_capacity(12);
+    for chunk in digest[..9].chunks(3) {
+        let bits = (chunk[0] as u32) << 16 | (chunk[1] as u32) << 8 | chunk[2] as u32;
+        for shift in [18, 12, 6, 0] {
+            output.push(ALPHABET[(bits >> shift & 0x3f) as usize] as char);
+        }
+    }
+    output
+}
@@ -0,0 +1,226 @@
+// This is free and unencumbered software released into the public domain.
+
+//! Graphviz DOT and GraphML export of the bookmark folder tree.
+//!
+//! Each profile, root and folder becomes a node, linked to its parent by an
+//! edge and labeled with its number of bookmarks. Optionally, each bookmark
+//! becomes a leaf node too.
+
+use super::{profile_label, write_xml_escaped};
+use crate::browsers::ProfileBookmarks;
+use crate::{BookmarkFolder, BookmarkNode};
+use std::format;
+use std::string::String;
+use std::vec::Vec;
+
+/// Renders the folder tree of one or more profiles as a Graphviz DOT graph,
+/// with a leaf node per bookmark if `leaves` is set.
+pub fn to_dot(profiles: &[ProfileBookmarks], leaves: bool) -> String {
+    let graph = Graph::new(profiles, leaves);
+    let mut output = String::from("digraph bookmarks {\n");
+    output.push_str("  rankdir=LR;\n");
+    output.push_str("  node [shape=folder];\n");
+    for (id, node) in graph.nodes.iter().enumerate() {
+        output.push_str(&format!("  n{id} [label="));
+        match node.kind {
+            Kind::Bookmark => write_dot_string(&mut output, &node.label),
+            _ => write_dot_string(&mut output, &format!("{}\n{}", node.label, node.counts())),
+        }
+        match node.kind {
+            Kind::Profile => output.push_str(", shape=box3d"),
+            Kind::Root => output.push_str(", style=bold"),
+            Kind::Folder => {},
+            Kind::Bookmark => output.push_str(", shape=note"),
+        }
+        if let Some(url) = node.url {
+            output.push_str(", URL=");
+            write_dot_string(&mut output, url);
+        }
+        output.push_str("];\n");
+    }
+    for (source, target) in &graph.edges {
+        output.push_str(&format!("  n{source} -> n{target};\n"));
+    }
+    output.push_str("}\n");
+    output
+}
+
+/// Renders the folder tree of one or more profiles as a GraphML document,
+/// with a leaf node per bookmark if `leaves` is set.
+pub fn to_graphml(profiles: &[ProfileBookmarks], leaves: bool) -> String {
+    let graph = Graph::new(profiles, leaves);
+    let mut output = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    output.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+    for (key, kind) in [
+        ("kind", "string"),
+        ("label", "string"),
+        ("url", "string"),
+        ("bookmarks", "int"),
+        ("total", "int"),
+    ] {
+        output.push_str(&format!(
+            "  <key id=\"{key}\" for=\"node\" attr.name=\"{key}\" attr.type=\"{kind}\"/>\n"
+        ));
+    }
+    output.push_str("  <graph id=\"bookmarks\" edgedefault=\"directed\">\n");
+    for (id, node) in graph.nodes.iter().enumerate() {
+        output.push_str(&format!("    <node id=\"n{id}\">\n"));
+        write_graphml_data(&mut output, "kind", node.kind.name());
+        write_graphml_data(&mut output, "label", &node.label);
+        if let Some(url) = node.url {
+            write_graphml_data(&mut output, "url", url);
+        }
+        if node.kind != Kind::Bookmark {
+            write_graphml_data(&mut output, "bookmarks", &format!("{}", node.bookmarks));
+            write_graphml_data(&mut output, "total", &format!("{}", node.total));
+        }
+        output.push_str("    </node>\n");
+    }
+    for (source, target) in &graph.edges {
+        output.push_str(&format!(
+            "    <edge source=\"n{source}\" target=\"n{target}\"/>\n"
+        ));
+    }
+    output.push_str("  </graph>\n</graphml>\n");
+    output
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Profile,
+    Root,
+    Folder,
+    Bookmark,
+}
+
+impl Kind {
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Profile => "profile",
+            Kind::Root => "root",
+            Kind::Folder => "folder",
+            Kind::Bookmark => "bookmark",
+        }
+    }
+}
+
+struct Node<'a> {
+    kind: Kind,
+    label: String,
+    url: Option<&'a str>,
+    /// The number of bookmarks directly in this folder.
+    bookmarks: usize,
+    /// The number of bookmarks in this folder and its subfolders.
+    total: usize,
+}
+
+impl Node<'_> {
+    /// Describes the bookmark counts, e.g., `2 bookmarks, 5 in total`.
+    fn counts(&self) -> String {
+        if self.kind == Kind::Profile || self.total == self.bookmarks {
+            return count(self.total);
+        }
+        format!("{}, {} in total", count(self.bookmarks), self.total)
+    }
+}
+
+fn count(bookmarks: usize) -> String {
+    match bookmarks {
+        1 => String::from("1 bookmark"),
+        count => format!("{count} bookmarks"),
+    }
+}
+
+struct Graph<'a> {
+    nodes: Vec<Node<'a>>,
+    edges: Vec<(usize, usize)>,
+    leaves: bool,
+}
+
+impl<'a> Graph<'a> {
+    fn new(profiles: &'a [ProfileBookmarks], leaves: bool) -> Self {
+        let mut graph = Graph {
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            leaves,
+        };
+        for profile in profiles {
+            let id = graph.nodes.len();
+            graph.nodes.push(Node {
+                kind: Kind::Profile,
+                label: profile_label(profile),
+                url: None,
+                bookmarks: 0,
+                total: 0,
+            });
+            let mut total = 0;
+            for (_, root) in profile.bookmarks.roots.iter() {
+                total += graph.push_folder(root, Kind::Root, id);
+            }
+            graph.nodes[id].total = total;
+        }
+        graph
+    }
+
+    /// Adds a folder and its descendants, returning its total bookmark count.
+    fn push_folder(&mut self, folder: &'a BookmarkFolder, kind: Kind, parent: usize) -> usize {
+        let id = self.nodes.len();
+        let bookmarks = folder
+            .children
+            .iter()
+            .filter(|child| child.as_url().is_some())
+            .count();
+        self.nodes.push(Node {
+            kind,
+            label: folder.name.clone(),
+            url: None,
+            bookmarks,
+            total: 0,
+        });
+        self.edges.push((parent, id));
+
+        let mut total = bookmarks;
+        for child in &folder.children {
+            match child {
+                BookmarkNode::Url(url) if self.leaves => {
+                    self.edges.push((id, self.nodes.len()));
+                    self.nodes.push(Node {
+                        kind: Kind::Bookmark,
+                        label: match url.name.as_str() {
+                            "" => url.url.clone(),
+                            name => name.into(),
+                        },
+                        url: Some(&url.url),
+                        bookmarks: 0,
+                        total: 0,
+                    });
+                },
+                BookmarkNode::Url(_) => {},
+                BookmarkNode::Folder(child) => total += self.push_folder(child, Kind::Folder, id),
+            }
+        }
+        self.nodes[id].total = total;
+        total
+    }
+}
+
+/// Writes a quoted DOT string, keeping line breaks as `\n` escapes.
+fn write_dot_string(output: &mut String, text: &str) {
+    output.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => {},
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+}
+
+fn write_graphml_data(output: &mut String, key: &str, value: &str) {
+    output.push_str(&format!("      <data key=\"{key}\">"));
+    write_xml_escaped(output, value);
+    output.push_str("</data>\n");
+}
@@ -0,0 +1,330 @@
+// This is free and unencumbered software released into the public domain.
+
+//! Markdown export, either as a single document or as an Obsidian vault.
+//!
+//! In the document, each folder becomes a heading nested under its parent,
+//! with the folder's bookmarks listed before its subfolders. In the vault,
+//! every folder and bookmark becomes a note with YAML front matter.
+
+use super::profile_label;
+use crate::browsers::ProfileBookmarks;
+use crate::{BookmarkFolder, BookmarkNode, BookmarkUrl, format_webkit_time};
+use miette::{IntoDiagnostic, Result, WrapErr};
+use std::collections::BTreeSet;
+use std::format;
+use std::path::{Path, PathBuf};
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+/// Renders the bookmarks of one or more profiles as a Markdown document.
+pub fn to_markdown(profiles: &[ProfileBookmarks]) -> String {
+    let mut output = String::new();
+    for profile in profiles {
+        if !output.is_empty() {
+            output.push('\n');
+        }
+        output.push_str("# ");
+        output.push_str(&escape(&profile_label(profile)));
+        output.push('\n');
+        for (_, root) in profile.bookmarks.roots.iter() {
+            write_folder(&mut output, root, 2);
+        }
+    }
+    output
+}
+
+fn write_folder(output: &mut String, folder: &BookmarkFolder, level: usize) {
+    output.push('\n');
+    output.push_str(&"#".repeat(level.min(6)));
+    output.push(' ');
+    output.push_str(&escape(&folder.name));
+    output.push_str("\n\n");
+
+    let urls: Vec<_> = folder
+        .children
+        .iter()
+        .filter_map(BookmarkNode::as_url)
+        .collect();
+    for url in &urls {
+        output.push_str("- ");
+        write_link(output, url);
+        let dates = [
+            ("added", url.date_added.and_then(format_date)),
+            ("last used", url.date_last_used.and_then(format_date)),
+        ];
+        for (index, (label, date)) in dates
+            .into_iter()
+            .filter_map(|(label, date)| Some((label, date?)))
+            .enumerate()
+        {
+            output.push_str(if index == 0 { " — " } else { ", " });
+            output.push_str(label);
+            output.push(' ');
+            output.push_str(&date);
+        }
+        output.push('\n');
+    }
+    if urls.is_empty() {
+        output.push_str("*No bookmarks.*\n");
+    }
+
+    for child in folder.children.iter().filter_map(BookmarkNode::as_folder) {
+        write_folder(output, child, level + 1);
+    }
+}
+
+fn write_link(output: &mut String, url: &BookmarkUrl) {
+    output.push('[');
+    output.push_str(&escape(if url.name.is_empty() {
+        &url.url
+    } else {
+        &url.name
+    }));
+    output.push_str("](");
+    for c in url.url.chars() {
+        match c {
+            ' ' | '(' | ')' | '<' | '>' => output.push_str(&format!("%{:02X}", c as u32)),
+            c => output.push(c),
+        }
+    }
+    output.push(')');
+}
+
+/// Writes the bookmarks of one or more profiles into a directory as an
+/// Obsidian vault, returning the paths of the notes written.
+///
+/// The folder hierarchy is mirrored as directories, each holding a note for
+/// the folder itself and one per bookmark. Existing notes are overwritten.
+pub fn write_vault(profiles: &[ProfileBookmarks], directory: &Path) -> Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    for (path, contents) in vault_notes(profiles) {
+        let path = directory.join(path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .into_diagnostic()
+                .wrap_err_with(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+        std::fs::write(&path, contents)
+            .into_diagnostic()
+            .wrap_err_with(|| format!("Failed to write note: {}", path.display()))?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+/// Renders the notes of an Obsidian vault, keyed by their relative path.
+pub fn vault_notes(profiles: &[ProfileBookmarks]) -> Vec<(PathBuf, String)> {
+    let mut notes = Vec::new();
+    let mut top_level = BTreeSet::new();
+    for profile in profiles {
+        // With several profiles, each gets its own top-level directory:
+        let directory = match profiles.len() {
+            1 => String::new(),
+            _ => unique_name(&mut top_level, &file_name(&profile_label(profile))),
+        };
+        let mut names = BTreeSet::new();
+        for (_, root) in profile.bookmarks.roots.iter() {
+            let name = unique_name(&mut names, &file_name(&root.name));
+            let directory = join(&directory, &name);
+            push_folder_notes(&mut notes, root, &directory, &name, &[]);
+        }
+    }
+    notes
+        .into_iter()
+        .map(|(path, contents)| (PathBuf::from(format!("{path}.md")), contents))
+        .collect()
+}
+
+/// Adds the notes of a folder, which lives in `directory` under `name`.
+fn push_folder_notes(
+    notes: &mut Vec<(String, String)>,
+    folder: &BookmarkFolder,
+    directory: &str,
+    name: &str,
+    tags: &[&str],
+) {
+    let mut tags = tags.to_vec();
+    tags.push(&folder.name);
+    let tag = tag_path(&tags);
+
+    let mut names = BTreeSet::from([name.to_lowercase()]);
+    let mut members = Vec::new();
+    for child in &folder.children {
+        let child_name = unique_name(&mut names, &file_name(child.name()));
+        match child {
+            BookmarkNode::Url(url) => {
+                let path = join(directory, &child_name);
+                members.push((path.clone(), url.name.as_str()));
+                notes.push((path, bookmark_note(url, &tag)));
+            },
+            BookmarkNode::Folder(child) => {
+                let child_directory = join(directory, &child_name);
+                members.push((join(&child_directory, &child_name), child.name.as_str()));
+                push_folder_notes(notes, child, &child_directory, &child_name, &tags);
+            },
+        }
+    }
+
+    let mut note = String::from("---\n");
+    push_yaml(&mut note, "title", Some(&yaml_string(&folder.name)));
+    push_yaml(
+        &mut note,
+        "created",
+        folder.date_added.and_then(format_webkit_time).as_deref(),
+    );
+    push_yaml(
+        &mut note,
+        "modified",
+        folder.date_modified.and_then(format_webkit_time).as_deref(),
+    );
+    if tags.len() > 1 {
+        push_yaml(
+            &mut note,
+            "tags",
+            Some(&format!("\n  - {}", tag_path(&tags[..tags.len() - 1]))),
+        );
+    }
+    push_yaml(&mut note, "guid", folder.guid.as_deref());
+    note.push_str("---\n\n# ");
+    note.push_str(&escape(&folder.name));
+    note.push_str("\n\n");
+    for (path, title) in &members {
+        note.push_str(&format!("- [[{path}|{}]]\n", wikilink_title(title)));
+    }
+    notes.push((join(directory, name), note));
+}
+
+fn bookmark_note(url: &BookmarkUrl, tag: &str) -> String {
+    let mut note = String::from("---\n");
+    push_yaml(&mut note, "title", Some(&yaml_string(&url.name)));
+    push_yaml(&mut note, "url", Some(&yaml_string(&url.url)));
+    push_yaml(
+        &mut note,
+        "created",
+        url.date_added.and_then(format_webkit_time).as_deref(),
+    );
+    push_yaml(
+        &mut note,
+        "last_used",
+        url.date_last_used.and_then(format_webkit_time).as_deref(),
+    );
+    push_yaml(&mut note, "tags", Some(&format!("\n  - {tag}")));
+    push_yaml(&mut note, "guid", url.guid.as_deref());
+    note.push_str("---\n\n");
+    write_link(&mut note, url);
+    note.push('\n');
+    note
+}
+
+fn push_yaml(note: &mut String, key: &str, value: Option<&str>) {
+    if let Some(value) = value {
+        note.push_str(key);
+        note.push(':');
+        if !value.starts_with('\n') {
+            note.push(' ');
+        }
+        note.push_str(value);
+        note.push('\n');
+    }
+}
+
+/// Quotes a YAML scalar as a double-quoted string.
+fn yaml_string(value: &str) -> String {
+    let mut output = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\t' => output.push_str("\\t"),
+            c if c.is_control() => output.push_str(&format!("\\u{:04X}", c as u32)),
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+    output
+}
+
+/// Builds a nested Obsidian tag (e.g., `Bookmarks-bar/Rust`) from folder names.
+fn tag_path(folders: &[&str]) -> String {
+    let segments: Vec<String> = folders
+        .iter()
+        .map(|name| {
+            let segment: String = name
+                .chars()
+                .map(|c| match c {
+                    c if c.is_alphanumeric() || c == '_' || c == '-' => c,
+                    _ => '-',
+                })
+                .collect();
+            match segment.trim_matches('-') {
+                "" => "_".to_string(),
+                segment => segment.to_string(),
+            }
+        })
+        .collect();
+    segments.join("/")
+}
+
+/// Makes a title safe to use as a file name.
+fn file_name(title: &str) -> String {
+    let name: String = title
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | '#' | '^' | '[' | ']' => '-',
+            c if c.is_control() => ' ',
+            c => c,
+        })
+        .take(100)
+        .collect();
+    match name.trim().trim_matches('.') {
+        "" => "Untitled".to_string(),
+        name => name.to_string(),
+    }
+}
+
+/// Returns the name, or the name with a numeric suffix if it is taken.
+fn unique_name(names: &mut BTreeSet<String>, name: &str) -> String {
+    let mut candidate = name.to_string();
+    let mut counter = 1;
+    // Compare case-insensitively, as file systems may:
+    while names.contains(&candidate.to_lowercase()) {
+        counter += 1;
+        candidate = format!("{name} {counter}");
+    }
+    names.insert(candidate.to_lowercase());
+    candidate
+}
+
+fn join(directory: &str, name: &str) -> String {
+    match directory {
+        "" => name.to_string(),
+        directory => format!("{directory}/{name}"),
+    }
+}
+
+fn wikilink_title(title: &str) -> String {
+    title.replace(['[', ']', '|', '\n'], " ")
+}
+
+fn format_date(timestamp: i64) -> Option<String> {
+    let mut date = format_webkit_time(timestamp)?;
+    date.truncate(10);
+    Some(date)
+}
+
+/// Escapes Markdown inline syntax, and folds line breaks into spaces.
+fn escape(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|' => {
+                output.push('\\');
+                output.push(c);
+            },
+            '\r' | '\n' | '\t' => output.push(' '),
+            c => output.push(c),
+        }
+    }
+    output
+}
@@ -0,0 +1,324 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::browsers::ProfileBookmarks;
+use crate::{BookmarkFolder, BookmarkNode, BookmarkUrl, BookmarksFile, BookmarksTransform};
+use core::{fmt, str::FromStr};
+use miette::{IntoDiagnostic, Result, miette};
+use std::format;
+use std::string::String;
+use std::vec::Vec;
+
+pub mod atom;
+pub mod csv;
+pub mod firefox;
+pub mod graph;
+pub mod markdown;
+pub mod netscape;
+pub mod opml;
+pub mod pinboard;
+pub mod rdf;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+/// The supported output formats.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// JSON-LD, one document per profile.
+    #[default]
+    JsonLd,
+    /// The raw Chromium bookmarks JSON, one document per profile.
+    Json,
+    /// One raw Chromium bookmark node per line.
+    JsonLines,
+    /// The JSON-LD graph as Turtle.
+    Turtle,
+    /// The JSON-LD graph as N-Triples.
+    NTriples,
+    /// The JSON-LD graph as TriG, with one named graph per profile.
+    TriG,
+    /// The JSON-LD graph as N-Quads, with one named graph per profile.
+    NQuads,
+    /// A Netscape bookmark file (`bookmarks.html`).
+    Html,
+    /// Comma-separated values, one row per bookmark.
+    Csv,
+    /// Tab-separated values, one row per bookmark.
+    Tsv,
+    /// A Markdown document with nested headings for folders.
+    Markdown,
+    /// An OPML 2.0 outline of the folder tree.
+    Opml,
+    /// An Atom 1.0 feed of the most recently added bookmarks.
+    Atom,
+    /// A Firefox bookmark backup, for "Restore from JSON".
+    Firefox,
+    /// Pinboard JSON, one post per bookmark.
+    Pinboard,
+    /// A Graphviz DOT graph of the folder tree.
+    Dot,
+    /// A GraphML graph of the folder tree.
+    GraphMl,
+}
+
+impl OutputFormat {
+    /// The names accepted by [`OutputFormat::from_str`], one per format.
+    pub const NAMES: &[&str] = &[
+        "jsonld", "json", "jsonl", "turtle", "ntriples", "trig", "nquads", "html", "csv", "tsv",
+        "markdown", "opml", "atom", "firefox", "pinboard", "dot", "graphml",
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            OutputFormat::JsonLd => "jsonld",
+            OutputFormat::Json => "json",
+            OutputFormat::JsonLines => "jsonl",
+            OutputFormat::Turtle => "turtle",
+            OutputFormat::NTriples => "ntriples",
+            OutputFormat::TriG => "trig",
+            OutputFormat::NQuads => "nquads",
+            OutputFormat::Html => "html",
+            OutputFormat::Csv => "csv",
+            OutputFormat::Tsv => "tsv",
+            OutputFormat::Markdown => "markdown",
+            OutputFormat::Opml => "opml",
+            OutputFormat::Atom => "atom",
+            OutputFormat::Firefox => "firefox",
+            OutputFormat::Pinboard => "pinboard",
+            OutputFormat::Dot => "dot",
+            OutputFormat::GraphMl => "graphml",
+        }
+    }
+
+    /// Whether the format is rendered from the JSON-LD transform output.
+    pub fn uses_transform(&self) -> bool {
+        matches!(
+            self,
+            OutputFormat::JsonLd
+                | OutputFormat::Turtle
+                | OutputFormat::NTriples
+                | OutputFormat::TriG
+                | OutputFormat::NQuads
+        )
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = miette::Report;
+
+    fn from_str(input: &str) -> Result<Self> {
+        Ok(match input.to_ascii_lowercase().as_str() {
+            "jsonld" | "json-ld" => OutputFormat::JsonLd,
+            "json" => OutputFormat::Json,
+            "jsonl" | "ndjson" => OutputFormat::JsonLines,
+            "turtle" | "ttl" => OutputFormat::Turtle,
+            "ntriples" | "nt" => OutputFormat::NTriples,
+            "trig" => OutputFormat::TriG,
+            "nquads" | "nq" => OutputFormat::NQuads,
+            "html" | "netscape" => OutputFormat::Html,
+            "csv" => OutputFormat::Csv,
+            "tsv" => OutputFormat::Tsv,
+            "markdown" | "md" => OutputFormat::Markdown,
+            "opml" => OutputFormat::Opml,
+            "atom" => OutputFormat::Atom,
+            "firefox" => OutputFormat::Firefox,
+            "pinboard" => OutputFormat::Pinboard,
+            "dot" | "graphviz" | "gv" => OutputFormat::Dot,
+            "graphml" => OutputFormat::GraphMl,
+            _ => {
+                return Err(miette!(
+                    help = format!("Supported formats: {}", OutputFormat::NAMES.join(", ")),
+                    "Unknown output format: {}",
+                    input
+                ));
+            },
+        })
+    }
+}
+
+/// Renders bookmarks in an output format.
+#[derive(Clone, Default)]
+pub struct Exporter {
+    format: OutputFormat,
+    transform: BookmarksTransform,
+    limit: Option<usize>,
+    leaves: bool,
+}
+
+impl Exporter {
+    pub fn new(format: OutputFormat) -> Self {
+        Self {
+            format,
+            ..Default::default()
+        }
+    }
+
+    /// Uses the given transform for JSON-LD and RDF output.
+    pub fn with_transform(mut self, transform: BookmarksTransform) -> Self {
+        self.transform = transform;
+        self
+    }
+
+    /// Limits the number of entries in an Atom feed.
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Includes a leaf node for every bookmark in DOT and GraphML graphs.
+    pub fn with_leaves(mut self, leaves: bool) -> Self {
+        self.leaves = leaves;
+        self
+    }
+
+    pub fn format(&self) -> OutputFormat {
+        self.format
+    }
+
+    /// Renders the bookmarks of one or more profiles.
+    pub fn export(&self, profiles: &[ProfileBookmarks]) -> Result<String> {
+        match self.format {
+            OutputFormat::JsonLd | OutputFormat::Json | OutputFormat::JsonLines => {
+                self.export_json(profiles)
+            },
+            OutputFormat::Turtle
+            | OutputFormat::NTriples
+            | OutputFormat::TriG
+            | OutputFormat::NQuads => self.export_rdf(profiles),
+            OutputFormat::Html => Ok(netscape::to_html(profiles)),
+            OutputFormat::Csv => Ok(csv::to_csv(profiles)),
+            OutputFormat::Tsv => Ok(csv::to_tsv(profiles)),
+            OutputFormat::Markdown => Ok(markdown::to_markdown(profiles)),
+            OutputFormat::Opml => Ok(opml::to_opml(profiles)),
+            OutputFormat::Atom => Ok(atom::to_atom(
+                profiles,
+                self.limit.unwrap_or(atom::DEFAULT_LIMIT),
+            )),
+            OutputFormat::Firefox => firefox::to_firefox_json(profiles),
+            OutputFormat::Pinboard => pinboard::to_pinboard(profiles),
+            OutputFormat::Dot => Ok(graph::to_dot(profiles, self.leaves)),
+            OutputFormat::GraphMl => Ok(graph::to_graphml(profiles, self.leaves)),
+        }
+    }
+
+    /// Renders one JSON document per profile, or one JSON node per line.
+    fn export_json(&self, profiles: &[ProfileBookmarks]) -> Result<String> {
+        let mut output = String::new();
+        for profile in profiles {
+            let bookmarks = &profile.bookmarks;
+            match self.format {
+                OutputFormat::JsonLd => {
+                    let document = self
+                        .transform
+                        .execute_profile(profile)
+                        .map_err(|error| miette!("{error}"))?;
+                    output.push_str(&serde_json::to_string(&document).into_diagnostic()?);
+                    output.push('\n');
+                },
+                OutputFormat::JsonLines => {
+                    for (_, root) in bookmarks.roots.iter() {
+                        for node in root.walk().filter(|node| node.as_url().is_some()) {
+                            output.push_str(&serde_json::to_string(node).into_diagnostic()?);
+                            output.push('\n');
+                        }
+                    }
+                },
+                _ => {
+                    output.push_str(&serde_json::to_string(bookmarks).into_diagnostic()?);
+                    output.push('\n');
+                },
+            }
+        }
+        Ok(output)
+    }
+
+    /// Renders the JSON-LD graphs of all profiles as a single RDF document.
+    fn export_rdf(&self, profiles: &[ProfileBookmarks]) -> Result<String> {
+        let mut dataset = rdf::Dataset::new();
+        for profile in profiles {
+            let document = self
+                .transform
+                .execute_profile(profile)
+                .map_err(|error| miette!("{error}"))?;
+            // Bookmarks of unknown origin go into the default graph:
+            dataset.insert_jsonld(&document, profile.iri().map(rdf::Term::Iri))?;
+        }
+        Ok(match self.format {
+            OutputFormat::Turtle => dataset.to_turtle(),
+            OutputFormat::NTriples => dataset.to_ntriples(),
+            OutputFormat::TriG => dataset.to_trig(),
+            _ => dataset.to_nquads(),
+        })
+    }
+}
+
+/// Calls `f` for every bookmarked URL, in document order, with its root and
+/// the names of its ancestor folders below the root.
+pub(crate) fn for_each_url(
+    bookmarks: &BookmarksFile,
+    mut f: impl FnMut(&str, &[&str], &BookmarkUrl),
+) {
+    fn walk<'a>(
+        folder: &'a BookmarkFolder,
+        root: &str,
+        path: &mut Vec<&'a str>,
+        f: &mut impl FnMut(&str, &[&str], &BookmarkUrl),
+    ) {
+        for child in &folder.children {
+            match child {
+                BookmarkNode::Url(url) => f(root, path, url),
+                BookmarkNode::Folder(folder) => {
+                    path.push(&folder.name);
+                    walk(folder, root, path, f);
+                    path.pop();
+                },
+            }
+        }
+    }
+
+    for (root, folder) in bookmarks.roots.iter() {
+        walk(folder, root, &mut Vec::new(), &mut f);
+    }
+}
+
+/// A human-readable name for a profile, such as `Profile 1 (chrome)`.
+pub(crate) fn profile_label(profile: &ProfileBookmarks) -> String {
+    match (&profile.browser, &profile.profile) {
+        (Some(browser), Some(profile)) => format!("{profile} ({browser})"),
+        (None, Some(profile)) => profile.clone(),
+        _ => "Bookmarks".into(),
+    }
+}
+
+/// Indents a line by `depth` levels of `unit`, such as two spaces.
+pub(crate) fn write_indent(output: &mut String, depth: usize, unit: &str) {
+    for _ in 0..depth {
+        output.push_str(unit);
+    }
+}
+
+/// Escapes text for use in XML or HTML attributes and character data.
+pub(crate) fn write_xml_escaped(output: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' => output.push_str("&quot;"),
+            // HTML 4, as read by browsers' bookmark importers, has no `&apos;`:
+            '\'' => output.push_str("&#39;"),
+            // Keep line breaks and tabs from being collapsed:
+            '\n' => output.push_str("&#10;"),
+            '\r' => output.push_str("&#13;"),
+            '\t' => output.push_str("&#9;"),
+            // Other control characters are not allowed in XML 1.0:
+            c if (c as u32) < 0x20 => {},
+            c => output.push(c),
+        }
+    }
+}
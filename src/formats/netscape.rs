@@ -0,0 +1,479 @@
+// This is free and unencumbered software released into the public domain.
+
+//! Netscape bookmark file (`bookmarks.html`) export and import.
+//!
+//! This is the `NETSCAPE-Bookmark-file-1` format that browsers use to export
+//! and import bookmarks. Dates are written as Unix timestamps in seconds.
+
+use super::{write_indent, write_xml_escaped};
+use crate::browsers::ProfileBookmarks;
+use crate::{
+    BOOKMARK_BAR_GUID, BookmarkFolder, BookmarkNode, BookmarkUrl, BookmarksFile, OTHER_GUID, Roots,
+    SYNCED_GUID, name_based_guid, unix_micros_to_webkit, webkit_to_unix_micros,
+};
+use core::fmt::Write;
+use miette::{Result, miette};
+use std::format;
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+/// Netscape bookmark files are indented by four spaces per level.
+const INDENT: &str = "    ";
+
+const HEADER: &str = "\
+<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<!-- This is an automatically generated file.
+     It will be read and overwritten.
+     DO NOT EDIT! -->
+<META HTTP-EQUIV=\"Content-Type\" CONTENT=\"text/html; charset=UTF-8\">
+<TITLE>Bookmarks</TITLE>
+<H1>Bookmarks</H1>
+";
+
+/// Renders the bookmarks of one or more profiles as a Netscape bookmark
+/// file. With several profiles, each becomes a top-level folder.
+pub fn to_html(profiles: &[ProfileBookmarks]) -> String {
+    let mut output = String::from(HEADER);
+    output.push_str("<DL><p>\n");
+    match profiles {
+        [profile] => write_roots(&mut output, &profile.bookmarks, 1),
+        profiles => {
+            for profile in profiles {
+                let name = profile.profile.as_deref().unwrap_or("Bookmarks");
+                write_indent(&mut output, 1, INDENT);
+                output.push_str("<DT><H3>");
+                write_xml_escaped(&mut output, name);
+                output.push_str("</H3>\n");
+                write_indent(&mut output, 1, INDENT);
+                output.push_str("<DL><p>\n");
+                write_roots(&mut output, &profile.bookmarks, 2);
+                write_indent(&mut output, 1, INDENT);
+                output.push_str("</DL><p>\n");
+            }
+        },
+    }
+    output.push_str("</DL><p>\n");
+    output
+}
+
+fn write_roots(output: &mut String, bookmarks: &BookmarksFile, depth: usize) {
+    for (root, folder) in bookmarks.roots.iter() {
+        let attribute = match root {
+            "bookmark_bar" => Some("PERSONAL_TOOLBAR_FOLDER"),
+            "other" => Some("UNFILED_BOOKMARKS_FOLDER"),
+            _ => None,
+        };
+        write_folder(output, folder, attribute, depth);
+    }
+}
+
+fn write_folder(output: &mut String, folder: &BookmarkFolder, flag: Option<&str>, depth: usize) {
+    write_indent(output, depth, INDENT);
+    output.push_str("<DT><H3");
+    write_date(output, "ADD_DATE", folder.date_added);
+    write_date(output, "LAST_MODIFIED", folder.date_modified);
+    if let Some(flag) = flag {
+        write!(output, " {flag}=\"true\"").unwrap();
+    }
+    output.push('>');
+    write_xml_escaped(output, &folder.name);
+    output.push_str("</H3>\n");
+
+    write_indent(output, depth, INDENT);
+    output.push_str("<DL><p>\n");
+    for child in &folder.children {
+        match child {
+            BookmarkNode::Url(url) => write_url(output, url, depth + 1),
+            BookmarkNode::Folder(folder) => write_folder(output, folder, None, depth + 1),
+        }
+    }
+    write_indent(output, depth, INDENT);
+    output.push_str("</DL><p>\n");
+}
+
+fn write_url(output: &mut String, url: &BookmarkUrl, depth: usize) {
+    write_indent(output, depth, INDENT);
+    output.push_str("<DT><A HREF=\"");
+    write_xml_escaped(output, &url.url);
+    output.push('"');
+    write_date(output, "ADD_DATE", url.date_added);
+    write_date(output, "LAST_VISIT", url.date_last_used);
+    output.push('>');
+    write_xml_escaped(output, &url.name);
+    output.push_str("</A>\n");
+}
+
+fn write_date(output: &mut String, attribute: &str, timestamp: Option<i64>) {
+    if let Some(micros) = timestamp.and_then(webkit_to_unix_micros) {
+        write!(output, " {attribute}=\"{}\"", micros.div_euclid(1_000_000)).unwrap();
+    }
+}
+
+/// Parses a Netscape bookmark file into a Chromium bookmarks tree.
+///
+/// Top-level folders flagged `PERSONAL_TOOLBAR_FOLDER` or
+/// `UNFILED_BOOKMARKS_FOLDER`, and a top-level "Mobile bookmarks" folder,
+/// become the corresponding roots; anything else at the top level goes into
+/// other bookmarks. As the format has no GUIDs, name-based GUIDs are derived
+/// from each node's position and contents.
+pub fn from_html(html: &str) -> Result<BookmarksFile> {
+    let mut importer = Importer::default();
+    let mut tokens = Tokenizer { rest: html };
+    while let Some(token) = tokens.next() {
+        match token {
+            Token::Start { name, attributes } => match name.as_str() {
+                "H3" => {
+                    importer.flush_pending();
+                    let folder = BookmarkFolder {
+                        name: tokens.text_until("H3"),
+                        date_added: date(&attributes, "ADD_DATE"),
+                        date_modified: date(&attributes, "LAST_MODIFIED"),
+                        ..Default::default()
+                    };
+                    let root = match importer.folders.is_empty() {
+                        true => root_key(&attributes, &folder.name),
+                        false => None,
+                    };
+                    importer.pending = Some((folder, root));
+                },
+                "A" => {
+                    importer.flush_pending();
+                    let name = tokens.text_until("A");
+                    let Some(url) = attribute(&attributes, "HREF") else {
+                        continue;
+                    };
+                    let url = BookmarkUrl {
+                        name,
+                        url: url.to_string(),
+                        date_added: date(&attributes, "ADD_DATE"),
+                        date_last_used: date(&attributes, "LAST_VISIT"),
+                        ..Default::default()
+                    };
+                    importer.attach(BookmarkNode::Url(url), None);
+                },
+                "DL" => importer.open_list(),
+                _ => {},
+            },
+            Token::End(name) if name == "DL" => importer.close_list(),
+            _ => {},
+        }
+    }
+    importer.finish()
+}
+
+/// The state of [`from_html`] while walking the document.
+#[derive(Default)]
+struct Importer {
+    roots: Roots,
+    /// Top-level nodes that belong to the other bookmarks root.
+    other: Vec<BookmarkNode>,
+    /// The open folders, with the root each top-level folder maps to.
+    folders: Vec<(BookmarkFolder, Option<&'static str>)>,
+    /// Whether each open `<DL>` holds the children of a folder.
+    lists: Vec<bool>,
+    /// A folder heading whose `<DL>` has not been seen yet.
+    pending: Option<(BookmarkFolder, Option<&'static str>)>,
+    seen_list: bool,
+}
+
+impl Importer {
+    fn open_list(&mut self) {
+        self.seen_list = true;
+        match self.pending.take() {
+            Some(folder) => {
+                self.folders.push(folder);
+                self.lists.push(true);
+            },
+            None => self.lists.push(false),
+        }
+    }
+
+    fn close_list(&mut self) {
+        self.flush_pending();
+        if self.lists.pop() == Some(true)
+            && let Some((folder, root)) = self.folders.pop()
+        {
+            self.attach(BookmarkNode::Folder(folder), root);
+        }
+    }
+
+    /// Adds a folder heading that has no `<DL>` as an empty folder.
+    fn flush_pending(&mut self) {
+        if let Some((folder, root)) = self.pending.take() {
+            self.attach(BookmarkNode::Folder(folder), root);
+        }
+    }
+
+    fn attach(&mut self, node: BookmarkNode, root: Option<&'static str>) {
+        if let Some((parent, _)) = self.folders.last_mut() {
+            parent.children.push(node);
+            return;
+        }
+        let slot = match root {
+            Some("bookmark_bar") => &mut self.roots.bookmark_bar,
+            Some("other") => &mut self.roots.other,
+            Some("synced") => &mut self.roots.synced,
+            _ => {
+                self.other.push(node);
+                return;
+            },
+        };
+        match node {
+            BookmarkNode::Folder(folder) if slot.is_none() => *slot = Some(folder),
+            node => self.other.push(node),
+        }
+    }
+
+    fn finish(mut self) -> Result<BookmarksFile> {
+        self.flush_pending();
+        while let Some((folder, root)) = self.folders.pop() {
+            self.attach(BookmarkNode::Folder(folder), root);
+        }
+        if !self.seen_list {
+            return Err(miette!("Not a Netscape bookmark file: no <DL> list found"));
+        }
+
+        let mut roots = self.roots;
+        let defaults = [
+            (
+                &mut roots.bookmark_bar,
+                "bookmark_bar",
+                "Bookmarks bar",
+                BOOKMARK_BAR_GUID,
+            ),
+            (&mut roots.other, "other", "Other bookmarks", OTHER_GUID),
+            (&mut roots.synced, "synced", "Mobile bookmarks", SYNCED_GUID),
+        ];
+        for (root, key, name, guid) in defaults {
+            let root = root.get_or_insert_with(|| BookmarkFolder {
+                name: name.to_string(),
+                ..Default::default()
+            });
+            root.guid = Some(guid.to_string());
+            if key == "other" {
+                root.children.append(&mut self.other);
+            }
+            assign_guids(root, key);
+        }
+
+        Ok(BookmarksFile {
+            roots,
+            ..Default::default()
+        })
+    }
+}
+
+/// Maps a top-level folder heading to the root it was exported from.
+fn root_key(attributes: &[(String, String)], name: &str) -> Option<&'static str> {
+    let flag = |name| attribute(attributes, name).is_some_and(|value| value != "false");
+    if flag("PERSONAL_TOOLBAR_FOLDER") {
+        Some("bookmark_bar")
+    } else if flag("UNFILED_BOOKMARKS_FOLDER") {
+        Some("other")
+    } else if name == "Mobile bookmarks" {
+        Some("synced")
+    } else {
+        None
+    }
+}
+
+/// Derives GUIDs for the descendants of a folder that have none.
+fn assign_guids(folder: &mut BookmarkFolder, path: &str) {
+    for (position, child) in folder.children.iter_mut().enumerate() {
+        let path = format!("{path}/{position}");
+        match child {
+            BookmarkNode::Url(url) => {
+                url.guid
+                    .get_or_insert_with(|| name_based_guid(&[&path, &url.name, &url.url]));
+            },
+            BookmarkNode::Folder(folder) => {
+                folder
+                    .guid
+                    .get_or_insert_with(|| name_based_guid(&[&path, &folder.name]));
+                assign_guids(folder, &path);
+            },
+        }
+    }
+}
+
+fn attribute<'a>(attributes: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attributes
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+/// Parses a Unix timestamp attribute into a WebKit timestamp, dropping
+/// dates that are out of range.
+fn date(attributes: &[(String, String)], name: &str) -> Option<i64> {
+    let seconds: i64 = attribute(attributes, name)?.trim().parse().ok()?;
+    if seconds == 0 {
+        return None;
+    }
+    unix_micros_to_webkit(seconds.checked_mul(1_000_000)?)
+}
+
+/// A lexical token of the (tag soup) HTML the format uses.
+enum Token<'a> {
+    /// A start tag, with its upper-cased name and attribute names.
+    Start {
+        name: String,
+        attributes: Vec<(String, String)>,
+    },
+    /// An end tag, with its upper-cased name.
+    End(String),
+    Text(&'a str),
+}
+
+struct Tokenizer<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Tokenizer<'a> {
+    /// Collects the text up to the given end tag, decoding HTML entities.
+    fn text_until(&mut self, end: &str) -> String {
+        let mut text = String::new();
+        for token in self.by_ref() {
+            match token {
+                Token::Text(chunk) => text.push_str(chunk),
+                Token::End(name) if name == end => break,
+                _ => {},
+            }
+        }
+        unescape(text.trim())
+    }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.rest.is_empty() {
+                return None;
+            }
+            if let Some(rest) = self.rest.strip_prefix("<!--") {
+                self.rest = rest.find("-->").map_or("", |end| &rest[end + 3..]);
+                continue;
+            }
+            let is_tag = self.rest.starts_with('<')
+                && self.rest[1..]
+                    .starts_with(|c: char| c.is_ascii_alphabetic() || c == '/' || c == '!');
+            if !is_tag {
+                // Text runs to the next tag; a stray `<` is kept as text:
+                let skip = usize::from(self.rest.starts_with('<'));
+                let end = self.rest[skip..]
+                    .find('<')
+                    .map_or(self.rest.len(), |end| end + skip);
+                let (text, rest) = self.rest.split_at(end);
+                self.rest = rest;
+                return Some(Token::Text(text));
+            }
+
+            let end = tag_end(self.rest).unwrap_or(self.rest.len());
+            let tag = &self.rest[1..end];
+            self.rest = self.rest.get(end + 1..).unwrap_or_default();
+            if let Some(name) = tag.strip_prefix('/') {
+                return Some(Token::End(name.trim().to_ascii_uppercase()));
+            }
+            if tag.starts_with('!') {
+                continue; // <!DOCTYPE ...>
+            }
+            return Some(parse_start_tag(tag));
+        }
+    }
+}
+
+/// Finds the `>` that ends the tag at the start of the input.
+fn tag_end(input: &str) -> Option<usize> {
+    let mut quote = None;
+    for (index, c) in input.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if q == c => quote = None,
+            (None, '>') => return Some(index),
+            _ => {},
+        }
+    }
+    None
+}
+
+fn parse_start_tag(tag: &str) -> Token<'_> {
+    let tag = tag.trim_end_matches('/');
+    let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+    let name = tag[..name_end].to_ascii_uppercase();
+    let mut attributes = Vec::new();
+    let mut rest = tag[name_end..].trim_start();
+    while !rest.is_empty() {
+        let key_end = rest
+            .find(|c: char| c.is_whitespace() || c == '=')
+            .unwrap_or(rest.len());
+        let key = rest[..key_end].to_ascii_uppercase();
+        rest = rest[key_end..].trim_start();
+        let value = match rest.strip_prefix('=') {
+            Some(value) => {
+                let value = value.trim_start();
+                let (raw, remainder) = match value.chars().next() {
+                    Some(quote @ ('"' | '\'')) => {
+                        let value = &value[1..];
+                        let end = value.find(quote).unwrap_or(value.len());
+                        (&value[..end], value.get(end + 1..).unwrap_or_default())
+                    },
+                    _ => value.split_at(value.find(char::is_whitespace).unwrap_or(value.len())),
+                };
+                rest = remainder.trim_start();
+                unescape(raw)
+            },
+            None => String::new(),
+        };
+        if !key.is_empty() {
+            attributes.push((key, value));
+        }
+    }
+    Token::Start { name, attributes }
+}
+
+/// Decodes HTML character references.
+fn unescape(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest
+            .get(1..)
+            .and_then(|entity| entity.find(';').filter(|&end| end <= 10))
+            .and_then(|end| Some((decode_entity(&rest[1..=end])?, end + 2)));
+        match decoded {
+            Some((c, length)) => {
+                output.push(c);
+                rest = &rest[length..];
+            },
+            None => {
+                output.push('&');
+                rest = &rest[1..];
+            },
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+    Some(match entity {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => '\u{A0}',
+        _ => {
+            let number = entity.strip_prefix('#')?;
+            let code = match number.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => number.parse().ok()?,
+            };
+            char::from_u32(code)?
+        },
+    })
+}
@@ -0,0 +1,93 @@
+// This is free and unencumbered software released into the public domain.
+
+//! OPML 2.0 export of the bookmark folder tree.
+//!
+//! Each folder becomes an `<outline>` containing its children, and each
+//! bookmark an `<outline type="link">` with its `url`.
+
+use super::{profile_label, write_indent, write_xml_escaped};
+use crate::browsers::ProfileBookmarks;
+use crate::{BookmarkFolder, BookmarkNode, BookmarkUrl, webkit_to_unix_micros};
+use std::string::{String, ToString};
+
+/// Renders the bookmarks of one or more profiles as an OPML 2.0 document.
+/// With several profiles, each becomes a top-level outline.
+pub fn to_opml(profiles: &[ProfileBookmarks]) -> String {
+    let mut output = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    output.push_str("<opml version=\"2.0\">\n");
+    output.push_str("  <head>\n    <title>");
+    match profiles {
+        [profile] => write_xml_escaped(&mut output, &profile_label(profile)),
+        _ => output.push_str("Bookmarks"),
+    }
+    output.push_str("</title>\n  </head>\n  <body>\n");
+    match profiles {
+        [profile] => {
+            for (_, root) in profile.bookmarks.roots.iter() {
+                write_folder(&mut output, root, 2);
+            }
+        },
+        profiles => {
+            for profile in profiles {
+                write_indent(&mut output, 2, "  ");
+                output.push_str("<outline text=\"");
+                write_xml_escaped(&mut output, &profile_label(profile));
+                output.push_str("\">\n");
+                for (_, root) in profile.bookmarks.roots.iter() {
+                    write_folder(&mut output, root, 3);
+                }
+                write_indent(&mut output, 2, "  ");
+                output.push_str("</outline>\n");
+            }
+        },
+    }
+    output.push_str("  </body>\n</opml>\n");
+    output
+}
+
+fn write_folder(output: &mut String, folder: &BookmarkFolder, depth: usize) {
+    write_indent(output, depth, "  ");
+    output.push_str("<outline text=\"");
+    write_xml_escaped(output, &folder.name);
+    output.push('"');
+    write_date(output, "created", folder.date_added);
+    if folder.children.is_empty() {
+        output.push_str("/>\n");
+        return;
+    }
+    output.push_str(">\n");
+    for child in &folder.children {
+        match child {
+            BookmarkNode::Url(url) => write_url(output, url, depth + 1),
+            BookmarkNode::Folder(folder) => write_folder(output, folder, depth + 1),
+        }
+    }
+    write_indent(output, depth, "  ");
+    output.push_str("</outline>\n");
+}
+
+fn write_url(output: &mut String, url: &BookmarkUrl, depth: usize) {
+    write_indent(output, depth, "  ");
+    output.push_str("<outline text=\"");
+    write_xml_escaped(output, &url.name);
+    output.push_str("\" type=\"link\" url=\"");
+    write_xml_escaped(output, &url.url);
+    output.push('"');
+    write_date(output, "created", url.date_added);
+    output.push_str("/>\n");
+}
+
+/// Writes a date attribute in the RFC 822 format that OPML uses.
+fn write_date(output: &mut String, attribute: &str, timestamp: Option<i64>) {
+    let Some(timestamp) = timestamp
+        .and_then(webkit_to_unix_micros)
+        .and_then(|micros| jiff::Timestamp::from_microsecond(micros).ok())
+    else {
+        return;
+    };
+    output.push(' ');
+    output.push_str(attribute);
+    output.push_str("=\"");
+    output.push_str(&timestamp.strftime("%a, %d %b %Y %H:%M:%S GMT").to_string());
+    output.push('"');
+}
@@ -0,0 +1,62 @@
+// This is free and unencumbered software released into the public domain.
+
+//! Pinboard JSON export, as accepted by Pinboard, Raindrop.io and other link
+//! managers.
+//!
+//! Each bookmark becomes a post whose tags are the names of its folders below
+//! the root, with whitespace replaced by dashes. Posts are private and not
+//! marked "to read".
+
+use crate::browsers::ProfileBookmarks;
+use crate::format_webkit_time_with_precision;
+use md5::{Digest, Md5};
+use miette::{IntoDiagnostic, Result};
+use serde_json::{Map, Value};
+use std::format;
+use std::string::String;
+use std::vec::Vec;
+
+/// Renders the bookmarks of one or more profiles as a Pinboard JSON array.
+pub fn to_pinboard(profiles: &[ProfileBookmarks]) -> Result<String> {
+    let mut posts = Vec::new();
+    for profile in profiles {
+        super::for_each_url(&profile.bookmarks, |_, folders, url| {
+            let mut post = Map::new();
+            post.insert("href".into(), url.url.as_str().into());
+            post.insert("description".into(), url.name.as_str().into());
+            post.insert("extended".into(), "".into());
+            post.insert("hash".into(), url_hash(&url.url).into());
+            if let Some(time) = url
+                .date_added
+                .and_then(|date| format_webkit_time_with_precision(date, 0))
+            {
+                post.insert("time".into(), time.into());
+            }
+            post.insert("shared".into(), "no".into());
+            post.insert("toread".into(), "no".into());
+            post.insert("tags".into(), tags(folders).into());
+            posts.push(Value::Object(post));
+        });
+    }
+    let mut output = serde_json::to_string(&posts).into_diagnostic()?;
+    output.push('\n');
+    Ok(output)
+}
+
+/// Joins the folder names into space-separated tags.
+fn tags(folders: &[&str]) -> String {
+    let tags: Vec<String> = folders
+        .iter()
+        .map(|name| name.split_whitespace().collect::<Vec<_>>().join("-"))
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    tags.join(" ")
+}
+
+/// Pinboard identifies posts by the MD5 digest of their URL.
+fn url_hash(url: &str) -> String {
+    Md5::digest(url)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
@@ -0,0 +1,652 @@
+// This is free and unencumbered software released into the public domain.
+
+//! RDF serialization (Turtle, N-Triples, TriG, N-Quads) of the JSON-LD
+//! bookmark graph.
+//!
+//! This converts the compact JSON-LD documents produced by
+//! [`BookmarksTransform`](crate::BookmarksTransform) to quads, supporting
+//! the subset of JSON-LD the transform uses: prefixes and term definitions
+//! with `@id`, `@type`, `@language` and `@container` (`@set`, `@list`).
+
+use core::fmt::Write;
+use miette::{Result, miette};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::format;
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+const RDF: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+const XSD: &str = "http://www.w3.org/2001/XMLSchema#";
+
+/// An RDF term.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Term {
+    Iri(String),
+    BlankNode(String),
+    Literal {
+        value: String,
+        datatype: Option<String>,
+        language: Option<String>,
+    },
+}
+
+/// An RDF quad: a triple in the default graph or in a named graph.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Quad {
+    pub subject: Term,
+    pub predicate: Term,
+    pub object: Term,
+    pub graph: Option<Term>,
+}
+
+/// An RDF dataset built from one or more JSON-LD documents.
+#[derive(Clone, Debug, Default)]
+pub struct Dataset {
+    /// The namespace prefixes declared in the documents' contexts.
+    pub prefixes: BTreeMap<String, String>,
+    pub quads: Vec<Quad>,
+    blank_nodes: usize,
+    /// The blank nodes of the current document, by their label there.
+    labels: BTreeMap<String, Term>,
+    graph: Option<Term>,
+}
+
+/// The predicates and objects of one subject, in order of appearance.
+type Properties<'a> = Vec<(&'a Term, Vec<&'a Term>)>;
+
+/// A term definition from a JSON-LD context.
+#[derive(Default)]
+struct TermDefinition {
+    id: Option<String>,
+    datatype: Option<String>,
+    language: Option<String>,
+    list: bool,
+}
+
+/// A parsed JSON-LD context.
+#[derive(Default)]
+struct Context {
+    prefixes: BTreeMap<String, String>,
+    terms: BTreeMap<String, TermDefinition>,
+}
+
+impl Dataset {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the triples of a compact JSON-LD document to the given named
+    /// graph, or to the default graph. A document without any triples, such
+    /// as plain JSON without an `@context`, is an error.
+    pub fn insert_jsonld(&mut self, document: &Value, graph: Option<Term>) -> Result<()> {
+        self.graph = graph;
+        self.labels.clear();
+        let count = self.quads.len();
+        let nodes = match document {
+            Value::Array(nodes) => nodes.iter().collect(),
+            Value::Object(_) => Vec::from([document]),
+            _ => return Err(miette!("Expected a JSON-LD document, found: {}", document)),
+        };
+        for node in nodes {
+            let Value::Object(node) = node else {
+                return Err(miette!("Expected a JSON-LD node object, found: {}", node));
+            };
+            let context = Context::parse(node.get("@context"))?;
+            for (prefix, namespace) in &context.prefixes {
+                self.prefixes.insert(prefix.clone(), namespace.clone());
+            }
+            match node.get("@graph") {
+                Some(Value::Array(nodes)) => {
+                    for node in nodes {
+                        self.insert_value(&context, node)?;
+                    }
+                },
+                _ => {
+                    self.insert_node(&context, node)?;
+                },
+            }
+        }
+        if self.quads.len() == count {
+            return Err(miette!(
+                "The JSON-LD document has no RDF triples; properties need an @context term definition"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Serializes the union of all graphs as N-Triples.
+    pub fn to_ntriples(&self) -> String {
+        let mut output = String::new();
+        for quad in &self.quads {
+            write_ntriples_triple(&mut output, quad);
+            output.push_str(" .\n");
+        }
+        output
+    }
+
+    /// Serializes the dataset as N-Quads.
+    pub fn to_nquads(&self) -> String {
+        let mut output = String::new();
+        for quad in &self.quads {
+            write_ntriples_triple(&mut output, quad);
+            if let Some(graph) = &quad.graph {
+                output.push(' ');
+                write_ntriples_term(&mut output, graph);
+            }
+            output.push_str(" .\n");
+        }
+        output
+    }
+
+    /// Serializes the union of all graphs as Turtle, grouping triples by
+    /// subject.
+    pub fn to_turtle(&self) -> String {
+        let prefixes = self.turtle_prefixes();
+        let mut output = String::new();
+        write_prefixes(&mut output, &prefixes);
+        for (subject, properties) in group_by_subject(self.quads.iter()) {
+            output.push('\n');
+            write_turtle_subject(&mut output, &prefixes, subject, &properties, "");
+        }
+        output
+    }
+
+    /// Serializes the dataset as TriG, with one block per named graph.
+    pub fn to_trig(&self) -> String {
+        let prefixes = self.turtle_prefixes();
+        let mut output = String::new();
+        write_prefixes(&mut output, &prefixes);
+
+        let mut graphs: Vec<Option<&Term>> = Vec::new();
+        for quad in &self.quads {
+            if !graphs.contains(&quad.graph.as_ref()) {
+                graphs.push(quad.graph.as_ref());
+            }
+        }
+        for graph in graphs {
+            let quads = self
+                .quads
+                .iter()
+                .filter(|quad| quad.graph.as_ref() == graph);
+            output.push('\n');
+            match graph {
+                Some(graph) => {
+                    write_turtle_term(&mut output, &prefixes, graph);
+                    output.push_str(" {\n");
+                    for (index, (subject, properties)) in
+                        group_by_subject(quads).into_iter().enumerate()
+                    {
+                        if index > 0 {
+                            output.push('\n');
+                        }
+                        write_turtle_subject(&mut output, &prefixes, subject, &properties, "    ");
+                    }
+                    output.push_str("}\n");
+                },
+                None => {
+                    for (index, (subject, properties)) in
+                        group_by_subject(quads).into_iter().enumerate()
+                    {
+                        if index > 0 {
+                            output.push('\n');
+                        }
+                        write_turtle_subject(&mut output, &prefixes, subject, &properties, "");
+                    }
+                },
+            }
+        }
+        output
+    }
+
+    fn turtle_prefixes(&self) -> BTreeMap<String, String> {
+        let mut prefixes = self.prefixes.clone();
+        prefixes
+            .entry("rdf".to_string())
+            .or_insert_with(|| RDF.to_string());
+        prefixes
+            .entry("xsd".to_string())
+            .or_insert_with(|| XSD.to_string());
+        prefixes
+    }
+
+    fn new_blank_node(&mut self) -> Term {
+        self.blank_nodes += 1;
+        Term::BlankNode(format!("b{}", self.blank_nodes))
+    }
+
+    /// Expands a node identifier, which may be a blank node label. Labels
+    /// are local to their document, so every document gets its own nodes.
+    fn expand_id(&mut self, context: &Context, id: &str) -> Term {
+        let Some(label) = id.strip_prefix("_:") else {
+            return Term::Iri(context.expand_iri(id));
+        };
+        if let Some(node) = self.labels.get(label) {
+            return node.clone();
+        }
+        let node = self.new_blank_node();
+        self.labels.insert(label.to_string(), node.clone());
+        node
+    }
+
+    fn push(&mut self, subject: &Term, predicate: Term, object: Term) {
+        self.quads.push(Quad {
+            subject: subject.clone(),
+            predicate,
+            object,
+            graph: self.graph.clone(),
+        });
+    }
+
+    fn insert_node(&mut self, context: &Context, node: &Map<String, Value>) -> Result<Term> {
+        let subject = match node.get("@id").and_then(Value::as_str) {
+            Some(id) => self.expand_id(context, id),
+            None => self.new_blank_node(),
+        };
+
+        match node.get("@type") {
+            Some(Value::String(class)) => {
+                self.push(&subject, rdf("type"), Term::Iri(context.expand_iri(class)));
+            },
+            Some(Value::Array(classes)) => {
+                for class in classes.iter().filter_map(Value::as_str) {
+                    self.push(&subject, rdf("type"), Term::Iri(context.expand_iri(class)));
+                }
+            },
+            _ => {},
+        }
+
+        for (key, value) in node {
+            if key.starts_with('@') {
+                continue;
+            }
+            let default = TermDefinition::default();
+            let definition = context.terms.get(key).unwrap_or(&default);
+            let Some(predicate) = context.expand_property(key, definition) else {
+                continue; // unmapped terms are dropped, as in JSON-LD
+            };
+            let predicate = Term::Iri(predicate);
+            match value {
+                Value::Null => {},
+                Value::Array(values) if definition.list => {
+                    let list = self.insert_list(context, definition, values)?;
+                    self.push(&subject, predicate, list);
+                },
+                Value::Array(values) => {
+                    for value in values {
+                        if let Some(object) = self.insert_object(context, definition, value)? {
+                            self.push(&subject, predicate.clone(), object);
+                        }
+                    }
+                },
+                value => {
+                    if let Some(object) = self.insert_object(context, definition, value)? {
+                        self.push(&subject, predicate, object);
+                    }
+                },
+            }
+        }
+
+        Ok(subject)
+    }
+
+    fn insert_value(&mut self, context: &Context, value: &Value) -> Result<()> {
+        if let Value::Object(node) = value {
+            self.insert_node(context, node)?;
+        }
+        Ok(())
+    }
+
+    fn insert_list(
+        &mut self,
+        context: &Context,
+        definition: &TermDefinition,
+        values: &[Value],
+    ) -> Result<Term> {
+        let mut objects = Vec::new();
+        for value in values {
+            if let Some(object) = self.insert_object(context, definition, value)? {
+                objects.push(object);
+            }
+        }
+        let mut list = rdf("nil");
+        for object in objects.into_iter().rev() {
+            let node = self.new_blank_node();
+            self.push(&node, rdf("first"), object);
+            self.push(&node, rdf("rest"), list);
+            list = node;
+        }
+        Ok(list)
+    }
+
+    fn insert_object(
+        &mut self,
+        context: &Context,
+        definition: &TermDefinition,
+        value: &Value,
+    ) -> Result<Option<Term>> {
+        let datatype = definition.datatype.as_deref();
+        Ok(Some(match value {
+            Value::Null => return Ok(None),
+            Value::Object(object) if object.contains_key("@value") => {
+                let Some(value) = object.get("@value").filter(|value| !value.is_null()) else {
+                    return Ok(None);
+                };
+                Term::Literal {
+                    value: lexical_form(value),
+                    datatype: object
+                        .get("@type")
+                        .and_then(Value::as_str)
+                        .map(|datatype| context.expand_iri(datatype)),
+                    language: object
+                        .get("@language")
+                        .and_then(Value::as_str)
+                        .map(ToString::to_string),
+                }
+            },
+            Value::Object(node) => self.insert_node(context, node)?,
+            Value::Array(_) => return Err(miette!("Nested JSON-LD arrays are not supported")),
+            Value::String(string) if datatype == Some("@id") => self.expand_id(context, string),
+            Value::String(string) => Term::Literal {
+                value: string.clone(),
+                datatype: datatype.map(|datatype| context.expand_iri(datatype)),
+                language: match datatype {
+                    None => definition.language.clone(),
+                    Some(_) => None,
+                },
+            },
+            Value::Bool(_) => literal(value, datatype.unwrap_or("xsd:boolean"), context),
+            Value::Number(number) if number.is_f64() => {
+                literal(value, datatype.unwrap_or("xsd:double"), context)
+            },
+            Value::Number(_) => literal(value, datatype.unwrap_or("xsd:integer"), context),
+        }))
+    }
+}
+
+impl Context {
+    fn parse(context: Option<&Value>) -> Result<Self> {
+        let mut result = Context::default();
+        let Some(context) = context else {
+            return Ok(result);
+        };
+        let Value::Object(entries) = context else {
+            return Err(miette!("Only inline JSON-LD contexts are supported"));
+        };
+        for (key, value) in entries {
+            match value {
+                Value::String(iri) => {
+                    if iri.ends_with('/') || iri.ends_with('#') {
+                        result.prefixes.insert(key.clone(), iri.clone());
+                    }
+                    result.terms.insert(
+                        key.clone(),
+                        TermDefinition {
+                            id: Some(iri.clone()),
+                            ..Default::default()
+                        },
+                    );
+                },
+                Value::Object(definition) => {
+                    let string = |key: &str| {
+                        definition
+                            .get(key)
+                            .and_then(Value::as_str)
+                            .map(ToString::to_string)
+                    };
+                    result.terms.insert(
+                        key.clone(),
+                        TermDefinition {
+                            id: string("@id"),
+                            datatype: string("@type"),
+                            language: string("@language"),
+                            list: definition.get("@container").and_then(Value::as_str)
+                                == Some("@list"),
+                        },
+                    );
+                },
+                _ => {},
+            }
+        }
+        Ok(result)
+    }
+
+    /// Expands a compact IRI (`prefix:suffix`) using the context's prefixes.
+    fn expand_iri(&self, iri: &str) -> String {
+        if let Some((prefix, suffix)) = iri.split_once(':')
+            && !suffix.starts_with("//")
+            && let Some(namespace) = self.prefixes.get(prefix)
+        {
+            return format!("{namespace}{suffix}");
+        }
+        if prefix_free(iri)
+            && let Some(TermDefinition { id: Some(id), .. }) = self.terms.get(iri)
+        {
+            return self.expand_iri(id);
+        }
+        match iri {
+            "xsd:boolean" | "xsd:double" | "xsd:integer" => {
+                format!("{XSD}{}", iri.trim_start_matches("xsd:"))
+            },
+            _ => iri.to_string(),
+        }
+    }
+
+    /// Expands a property key to an IRI, if it is mapped.
+    fn expand_property(&self, key: &str, definition: &TermDefinition) -> Option<String> {
+        match &definition.id {
+            Some(id) => Some(self.expand_iri(id)),
+            None if !prefix_free(key) => Some(self.expand_iri(key)),
+            None => None,
+        }
+    }
+}
+
+/// Groups quads by subject and predicate, in order of appearance.
+fn group_by_subject<'a>(quads: impl Iterator<Item = &'a Quad>) -> Vec<(&'a Term, Properties<'a>)> {
+    let mut groups: Vec<(&Term, Properties)> = Vec::new();
+    let mut index = BTreeMap::new();
+    for quad in quads {
+        let position = *index.entry(&quad.subject).or_insert_with(|| {
+            groups.push((&quad.subject, Vec::new()));
+            groups.len() - 1
+        });
+        let properties = &mut groups[position].1;
+        match properties
+            .iter_mut()
+            .find(|(predicate, _)| **predicate == quad.predicate)
+        {
+            Some((_, objects)) => objects.push(&quad.object),
+            None => properties.push((&quad.predicate, Vec::from([&quad.object]))),
+        }
+    }
+    groups
+}
+
+fn prefix_free(iri: &str) -> bool {
+    !iri.contains(':')
+}
+
+fn rdf(name: &str) -> Term {
+    Term::Iri(format!("{RDF}{name}"))
+}
+
+fn literal(value: &Value, datatype: &str, context: &Context) -> Term {
+    Term::Literal {
+        value: lexical_form(value),
+        datatype: Some(context.expand_iri(datatype)),
+        language: None,
+    }
+}
+
+fn lexical_form(value: &Value) -> String {
+    match value {
+        Value::String(string) => string.clone(),
+        value => value.to_string(),
+    }
+}
+
+fn write_ntriples_triple(output: &mut String, quad: &Quad) {
+    write_ntriples_term(output, &quad.subject);
+    output.push(' ');
+    write_ntriples_term(output, &quad.predicate);
+    output.push(' ');
+    write_ntriples_term(output, &quad.object);
+}
+
+fn write_ntriples_term(output: &mut String, term: &Term) {
+    match term {
+        Term::Iri(iri) => write_iri(output, iri),
+        Term::BlankNode(label) => {
+            output.push_str("_:");
+            output.push_str(label);
+        },
+        Term::Literal {
+            value,
+            datatype,
+            language,
+        } => {
+            write_string(output, value);
+            if let Some(language) = language {
+                output.push('@');
+                output.push_str(language);
+            } else if let Some(datatype) = datatype.as_deref().filter(|dt| *dt != XSD_STRING) {
+                output.push_str("^^");
+                write_iri(output, datatype);
+            }
+        },
+    }
+}
+
+const XSD_STRING: &str = "http://www.w3.org/2001/XMLSchema#string";
+
+fn write_prefixes(output: &mut String, prefixes: &BTreeMap<String, String>) {
+    for (prefix, namespace) in prefixes {
+        output.push_str("@prefix ");
+        output.push_str(prefix);
+        output.push_str(": ");
+        write_iri(output, namespace);
+        output.push_str(" .\n");
+    }
+}
+
+fn write_turtle_subject(
+    output: &mut String,
+    prefixes: &BTreeMap<String, String>,
+    subject: &Term,
+    properties: &Properties,
+    indent: &str,
+) {
+    output.push_str(indent);
+    write_turtle_term(output, prefixes, subject);
+    for (index, (predicate, objects)) in properties.iter().enumerate() {
+        if index == 0 {
+            output.push(' ');
+        } else {
+            output.push_str(" ;\n    ");
+            output.push_str(indent);
+        }
+        write_turtle_predicate(output, prefixes, predicate);
+        for (index, object) in objects.iter().enumerate() {
+            if index == 0 {
+                output.push(' ');
+            } else {
+                output.push_str(",\n        ");
+                output.push_str(indent);
+            }
+            write_turtle_term(output, prefixes, object);
+        }
+    }
+    output.push_str(" .\n");
+}
+
+fn write_turtle_predicate(output: &mut String, prefixes: &BTreeMap<String, String>, term: &Term) {
+    match term {
+        Term::Iri(iri) if iri.strip_prefix(RDF) == Some("type") => output.push('a'),
+        term => write_turtle_term(output, prefixes, term),
+    }
+}
+
+fn write_turtle_term(output: &mut String, prefixes: &BTreeMap<String, String>, term: &Term) {
+    match term {
+        Term::Iri(iri) => write_turtle_iri(output, prefixes, iri),
+        Term::Literal {
+            value,
+            datatype: Some(datatype),
+            language: None,
+        } if datatype != XSD_STRING => {
+            if datatype == "http://www.w3.org/2001/XMLSchema#integer"
+                && !value.is_empty()
+                && value
+                    .trim_start_matches(['-', '+'])
+                    .bytes()
+                    .all(|byte| byte.is_ascii_digit())
+            {
+                output.push_str(value);
+            } else {
+                write_string(output, value);
+                output.push_str("^^");
+                write_turtle_iri(output, prefixes, datatype);
+            }
+        },
+        term => write_ntriples_term(output, term),
+    }
+}
+
+fn write_turtle_iri(output: &mut String, prefixes: &BTreeMap<String, String>, iri: &str) {
+    for (prefix, namespace) in prefixes {
+        if let Some(local) = iri.strip_prefix(namespace.as_str())
+            && is_prefixed_local_name(local)
+        {
+            output.push_str(prefix);
+            output.push(':');
+            output.push_str(local);
+            return;
+        }
+    }
+    write_iri(output, iri);
+}
+
+/// A conservative subset of Turtle's `PN_LOCAL` production.
+fn is_prefixed_local_name(local: &str) -> bool {
+    let mut chars = local.chars();
+    match chars.next() {
+        None => true,
+        Some(first) if first.is_ascii_alphanumeric() || first == '_' => local
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-'),
+        Some(_) => false,
+    }
+}
+
+fn write_iri(output: &mut String, iri: &str) {
+    output.push('<');
+    for c in iri.chars() {
+        match c {
+            '\u{00}'..='\u{20}' | '<' | '>' | '"' | '{' | '}' | '|' | '^' | '`' | '\\' => {
+                // These are not allowed in IRIs, so percent-encode them:
+                write!(output, "%{:02X}", c as u32).unwrap();
+            },
+            c => output.push(c),
+        }
+    }
+    output.push('>');
+}
+
+fn write_string(output: &mut String, value: &str) {
+    output.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            '\u{08}' => output.push_str("\\b"),
+            '\u{0C}' => output.push_str("\\f"),
+            c if c.is_control() => write!(output, "\\u{:04X}", c as u32).unwrap(),
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+}
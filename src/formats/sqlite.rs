@@ -0,0 +1,215 @@
+// This is free and unencumbered software released into the public domain.
+
+//! SQLite catalog export, for ad-hoc querying with SQL.
+//!
+//! The catalog has `browsers`, `profiles`, `folders` and `bookmarks` tables.
+//! Folders and bookmarks are keyed by their GUID within a profile, so that
+//! exporting into an existing catalog updates rows rather than duplicating
+//! them, and rows of an exported profile that are no longer in it are
+//! deleted. Dates are stored as ISO 8601 text.
+
+use crate::browsers::ProfileBookmarks;
+use crate::{BookmarkFolder, BookmarkNode, BookmarkStorage, format_webkit_time};
+use miette::{IntoDiagnostic, Result, WrapErr};
+use rusqlite::{Connection, Transaction, params};
+use std::format;
+use std::path::Path;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS browsers (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE -- e.g. 'chrome', or '' if unknown
+);
+
+CREATE TABLE IF NOT EXISTS profiles (
+    id INTEGER PRIMARY KEY,
+    browser_id INTEGER NOT NULL REFERENCES browsers (id),
+    name TEXT NOT NULL, -- e.g. 'Default', or '' if unknown
+    UNIQUE (browser_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS folders (
+    id INTEGER PRIMARY KEY,
+    profile_id INTEGER NOT NULL REFERENCES profiles (id),
+    guid TEXT NOT NULL,
+    parent_id INTEGER REFERENCES folders (id), -- NULL for roots
+    root TEXT NOT NULL,
+    position INTEGER,
+    title TEXT NOT NULL,
+    date_added TEXT,
+    date_modified TEXT,
+    date_last_used TEXT,
+    storage TEXT,
+    UNIQUE (profile_id, guid)
+);
+
+CREATE TABLE IF NOT EXISTS bookmarks (
+    id INTEGER PRIMARY KEY,
+    profile_id INTEGER NOT NULL REFERENCES profiles (id),
+    guid TEXT NOT NULL,
+    folder_id INTEGER NOT NULL REFERENCES folders (id),
+    root TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    url TEXT NOT NULL,
+    date_added TEXT,
+    date_last_used TEXT,
+    storage TEXT,
+    UNIQUE (profile_id, guid)
+);
+
+-- The rows written by this export, to delete those of removed nodes:
+CREATE TEMP TABLE IF NOT EXISTS seen_folders (id INTEGER PRIMARY KEY);
+CREATE TEMP TABLE IF NOT EXISTS seen_bookmarks (id INTEGER PRIMARY KEY);
+";
+
+/// Writes the bookmarks of one or more profiles into a SQLite database,
+/// creating it and its tables if needed.
+pub fn write_catalog(profiles: &[ProfileBookmarks], path: &Path) -> Result<()> {
+    let mut connection = Connection::open(path)
+        .into_diagnostic()
+        .wrap_err_with(|| format!("Failed to open SQLite database: {}", path.display()))?;
+    let transaction = connection.transaction().into_diagnostic()?;
+    transaction.execute_batch(SCHEMA).into_diagnostic()?;
+    for profile in profiles {
+        insert_profile(&transaction, profile)
+            .into_diagnostic()
+            .wrap_err("Failed to write bookmarks to SQLite database")?;
+    }
+    transaction.commit().into_diagnostic()
+}
+
+fn insert_profile(transaction: &Transaction, profile: &ProfileBookmarks) -> rusqlite::Result<()> {
+    let browser_id: i64 = transaction.query_row(
+        "INSERT INTO browsers (name) VALUES (?1)
+         ON CONFLICT (name) DO UPDATE SET name = excluded.name
+         RETURNING id",
+        params![profile.browser.as_deref().unwrap_or_default()],
+        |row| row.get(0),
+    )?;
+    let profile_id: i64 = transaction.query_row(
+        "INSERT INTO profiles (browser_id, name) VALUES (?1, ?2)
+         ON CONFLICT (browser_id, name) DO UPDATE SET name = excluded.name
+         RETURNING id",
+        params![browser_id, profile.profile.as_deref().unwrap_or_default()],
+        |row| row.get(0),
+    )?;
+
+    let catalog = Catalog {
+        transaction,
+        profile_id,
+    };
+    for (root, folder) in profile.bookmarks.roots.iter() {
+        catalog.insert_folder(folder, root, root, None)?;
+    }
+
+    // Delete the folders and bookmarks removed since the last export:
+    transaction.execute(
+        "DELETE FROM bookmarks WHERE profile_id = ?1
+         AND id NOT IN (SELECT id FROM temp.seen_bookmarks)",
+        params![profile_id],
+    )?;
+    transaction.execute(
+        "DELETE FROM folders WHERE profile_id = ?1
+         AND id NOT IN (SELECT id FROM temp.seen_folders)",
+        params![profile_id],
+    )?;
+    Ok(())
+}
+
+struct Catalog<'a> {
+    transaction: &'a Transaction<'a>,
+    profile_id: i64,
+}
+
+/// A folder's row ID and its children's position within it.
+type Parent = Option<(i64, usize)>;
+
+impl Catalog<'_> {
+    /// Upserts a folder and its descendants; `path` identifies nodes that
+    /// have no GUID by their position in the tree.
+    fn insert_folder(
+        &self,
+        folder: &BookmarkFolder,
+        root: &str,
+        path: &str,
+        parent: Parent,
+    ) -> rusqlite::Result<()> {
+        let folder_id: i64 = self.transaction.query_row(
+            "INSERT INTO folders (profile_id, guid, parent_id, root, position, title,
+                                  date_added, date_modified, date_last_used, storage)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+             ON CONFLICT (profile_id, guid) DO UPDATE SET
+                 parent_id = excluded.parent_id,
+                 root = excluded.root,
+                 position = excluded.position,
+                 title = excluded.title,
+                 date_added = excluded.date_added,
+                 date_modified = excluded.date_modified,
+                 date_last_used = excluded.date_last_used,
+                 storage = excluded.storage
+             RETURNING id",
+            params![
+                self.profile_id,
+                folder.guid.as_deref().unwrap_or(path),
+                parent.map(|(id, _)| id),
+                root,
+                parent.map(|(_, position)| position as i64),
+                folder.name,
+                folder.date_added.and_then(format_webkit_time),
+                folder.date_modified.and_then(format_webkit_time),
+                folder.date_last_used.and_then(format_webkit_time),
+                folder.storage.map(BookmarkStorage::as_str),
+            ],
+            |row| row.get(0),
+        )?;
+        self.transaction.execute(
+            "INSERT OR IGNORE INTO temp.seen_folders (id) VALUES (?1)",
+            params![folder_id],
+        )?;
+
+        for (position, child) in folder.children.iter().enumerate() {
+            let path = format!("{path}/{position}");
+            match child {
+                BookmarkNode::Folder(child) => {
+                    self.insert_folder(child, root, &path, Some((folder_id, position)))?;
+                },
+                BookmarkNode::Url(url) => {
+                    let bookmark_id: i64 = self.transaction.query_row(
+                        "INSERT INTO bookmarks (profile_id, guid, folder_id, root, position,
+                                                title, url, date_added, date_last_used, storage)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                         ON CONFLICT (profile_id, guid) DO UPDATE SET
+                             folder_id = excluded.folder_id,
+                             root = excluded.root,
+                             position = excluded.position,
+                             title = excluded.title,
+                             url = excluded.url,
+                             date_added = excluded.date_added,
+                             date_last_used = excluded.date_last_used,
+                             storage = excluded.storage
+                         RETURNING id",
+                        params![
+                            self.profile_id,
+                            url.guid.as_deref().unwrap_or(&path),
+                            folder_id,
+                            root,
+                            position as i64,
+                            url.name,
+                            url.url,
+                            url.date_added.and_then(format_webkit_time),
+                            url.date_last_used.and_then(format_webkit_time),
+                            url.storage.map(BookmarkStorage::as_str),
+                        ],
+                        |row| row.get(0),
+                    )?;
+                    self.transaction.execute(
+                        "INSERT OR IGNORE INTO temp.seen_bookmarks (id) VALUES (?1)",
+                        params![bookmark_id],
+                    )?;
+                },
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,3 +1,35 @@
 // This is free and unencumbered software released into the public domain.
 
+use miette::{IntoDiagnostic, Result, WrapErr};
+use phf::phf_map;
+use std::borrow::Cow;
+use std::format;
+use std::vec::Vec;
+
 pub const BOOKMARKS: &str = include_str!("jq/bookmarks.jq");
+
+pub const FLATTEN: &str = include_str!("jq/flatten.jq");
+
+/// The built-in jq filters, by name.
+pub static BUILTINS: phf::Map<&'static str, &'static str> = phf_map! {
+    "bookmarks" => BOOKMARKS,
+    "flatten" => FLATTEN,
+};
+
+/// Resolves a filter given either as the name of a built-in filter or as
+/// the path to a jq program.
+pub fn resolve(filter: &str) -> Result<Cow<'static, str>> {
+    match BUILTINS.get(filter) {
+        Some(program) => Ok(Cow::Borrowed(program)),
+        None => std::fs::read_to_string(filter)
+            .map(Cow::Owned)
+            .into_diagnostic()
+            .wrap_err_with(|| {
+                format!(
+                    "Failed to read jq filter '{}' (built-in filters: {})",
+                    filter,
+                    BUILTINS.keys().copied().collect::<Vec<_>>().join(", ")
+                )
+            }),
+    }
+}
@@ -8,7 +8,16 @@ extern crate std;
 
 pub mod bookmarks;
 pub mod browsers;
+pub mod checksum;
+pub mod formats;
+#[cfg(feature = "jq")]
 pub mod jq;
+pub mod model;
 pub mod specialized;
+pub mod storage;
+pub mod writer;
 
 pub use bookmarks::*;
+pub use checksum::*;
+pub use model::*;
+pub use storage::*;
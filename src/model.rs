@@ -0,0 +1,408 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::BookmarkStorage;
+use md5::{Digest, Md5};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::format;
+use std::string::String;
+use std::vec::Vec;
+
+/// The well-known GUID of the "Bookmarks bar" root folder.
+pub const BOOKMARK_BAR_GUID: &str = "0bc5d13f-2cba-5d74-951f-3f233fe6c908";
+
+/// The well-known GUID of the "Other bookmarks" root folder.
+pub const OTHER_GUID: &str = "82b081ec-3dd3-529c-8475-ab6c344590dd";
+
+/// The well-known GUID of the "Mobile bookmarks" root folder.
+pub const SYNCED_GUID: &str = "4cf2e351-0e85-532b-bb37-df045d8f8d0f";
+
+/// The offset between the WebKit epoch (1601-01-01) and the Unix epoch, in seconds.
+pub const WEBKIT_EPOCH_OFFSET: i64 = 11_644_473_600;
+
+/// A Chromium `Bookmarks` file.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct BookmarksFile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
+
+    #[serde(default)]
+    pub roots: Roots,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync_metadata: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<u32>,
+
+    /// Any further keys, kept as they are.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// The permanent root folders of a Chromium `Bookmarks` file.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Roots {
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "root")]
+    pub bookmark_bar: Option<BookmarkFolder>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "root")]
+    pub other: Option<BookmarkFolder>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "root")]
+    pub synced: Option<BookmarkFolder>,
+
+    /// Any further root folders, such as those added by Chromium forks.
+    #[serde(flatten, with = "extra_roots")]
+    pub extra: BTreeMap<String, BookmarkFolder>,
+}
+
+/// A bookmark tree node, either a URL or a folder.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum BookmarkNode {
+    Url(BookmarkUrl),
+    Folder(BookmarkFolder),
+}
+
+/// A bookmarked URL.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct BookmarkUrl {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub guid: Option<String>,
+
+    #[serde(default)]
+    pub name: String,
+
+    pub url: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "timestamp")]
+    pub date_added: Option<i64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "timestamp")]
+    pub date_last_used: Option<i64>,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub meta_info: BTreeMap<String, String>,
+
+    /// Where Chrome stores this node; not part of Chromium's file format,
+    /// so it is not serialized.
+    #[serde(skip)]
+    pub storage: Option<BookmarkStorage>,
+
+    /// Any further keys, such as `unsynced_meta_info`, kept as they are.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// A bookmark folder.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct BookmarkFolder {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub guid: Option<String>,
+
+    #[serde(default)]
+    pub name: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "timestamp")]
+    pub date_added: Option<i64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "timestamp")]
+    pub date_last_used: Option<i64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "timestamp")]
+    pub date_modified: Option<i64>,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub meta_info: BTreeMap<String, String>,
+
+    /// Where Chrome stores this node; not part of Chromium's file format,
+    /// so it is not serialized.
+    #[serde(skip)]
+    pub storage: Option<BookmarkStorage>,
+
+    /// Any further keys, such as `unsynced_meta_info`, kept as they are.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+
+    #[serde(default)]
+    pub children: Vec<BookmarkNode>,
+}
+
+impl Roots {
+    /// Returns the root folders that are present, keyed by their JSON name.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &BookmarkFolder)> {
+        [
+            ("bookmark_bar", self.bookmark_bar.as_ref()),
+            ("other", self.other.as_ref()),
+            ("synced", self.synced.as_ref()),
+        ]
+        .into_iter()
+        .filter_map(|(key, folder)| folder.map(|folder| (key, folder)))
+        .chain(
+            self.extra
+                .iter()
+                .map(|(key, folder)| (key.as_str(), folder)),
+        )
+    }
+}
+
+impl BookmarksFile {
+    /// Returns all bookmarked URLs, in document order.
+    pub fn urls(&self) -> impl Iterator<Item = &BookmarkUrl> {
+        self.roots
+            .iter()
+            .flat_map(|(_, root)| root.walk())
+            .filter_map(BookmarkNode::as_url)
+    }
+}
+
+impl BookmarkNode {
+    pub fn name(&self) -> &str {
+        match self {
+            BookmarkNode::Url(url) => &url.name,
+            BookmarkNode::Folder(folder) => &folder.name,
+        }
+    }
+
+    pub fn guid(&self) -> Option<&str> {
+        match self {
+            BookmarkNode::Url(url) => url.guid.as_deref(),
+            BookmarkNode::Folder(folder) => folder.guid.as_deref(),
+        }
+    }
+
+    pub fn date_added(&self) -> Option<i64> {
+        match self {
+            BookmarkNode::Url(url) => url.date_added,
+            BookmarkNode::Folder(folder) => folder.date_added,
+        }
+    }
+
+    pub fn as_url(&self) -> Option<&BookmarkUrl> {
+        match self {
+            BookmarkNode::Url(url) => Some(url),
+            BookmarkNode::Folder(_) => None,
+        }
+    }
+
+    pub fn as_folder(&self) -> Option<&BookmarkFolder> {
+        match self {
+            BookmarkNode::Url(_) => None,
+            BookmarkNode::Folder(folder) => Some(folder),
+        }
+    }
+}
+
+impl BookmarkFolder {
+    /// Returns all descendants of this folder, depth-first in document order.
+    pub fn walk(&self) -> Walk<'_> {
+        Walk {
+            stack: Vec::from([self.children.iter()]),
+        }
+    }
+}
+
+/// A depth-first iterator over the descendants of a folder.
+pub struct Walk<'a> {
+    stack: Vec<core::slice::Iter<'a, BookmarkNode>>,
+}
+
+impl<'a> Iterator for Walk<'a> {
+    type Item = &'a BookmarkNode;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let node = match self.stack.last_mut()?.next() {
+                Some(node) => node,
+                None => {
+                    self.stack.pop();
+                    continue;
+                },
+            };
+            if let BookmarkNode::Folder(folder) = node {
+                self.stack.push(folder.children.iter());
+            }
+            return Some(node);
+        }
+    }
+}
+
+/// Converts a WebKit timestamp (microseconds since 1601) to Unix microseconds.
+///
+/// Chromium uses zero for "never", which yields `None`, as do timestamps
+/// out of range.
+pub fn webkit_to_unix_micros(timestamp: i64) -> Option<i64> {
+    let micros = timestamp.checked_sub(WEBKIT_EPOCH_OFFSET * 1_000_000)?;
+    (timestamp != 0).then_some(micros)
+}
+
+/// Formats a WebKit timestamp as an ISO 8601 date-time in UTC, with
+/// microsecond precision (e.g., `2022-06-18T04:26:40.000000Z`).
+pub fn format_webkit_time(timestamp: i64) -> Option<String> {
+    format_webkit_time_with_precision(timestamp, 6)
+}
+
+/// Formats a WebKit timestamp as an ISO 8601 date-time in UTC, with the
+/// given number of fractional second digits (e.g., `2022-06-18T04:26:40Z`
+/// for none).
+pub fn format_webkit_time_with_precision(timestamp: i64, digits: usize) -> Option<String> {
+    let micros = webkit_to_unix_micros(timestamp)?;
+    let timestamp = jiff::Timestamp::from_microsecond(micros).ok()?;
+    Some(format!("{timestamp:.digits$}"))
+}
+
+/// Converts Unix microseconds to a WebKit timestamp (microseconds since 1601),
+/// or `None` if it is out of range.
+pub fn unix_micros_to_webkit(micros: i64) -> Option<i64> {
+    micros.checked_add(WEBKIT_EPOCH_OFFSET * 1_000_000)
+}
+
+/// Derives a name-based (version 3) UUID from the given parts.
+pub(crate) fn name_based_guid(parts: &[&str]) -> String {
+    let mut hasher = Md5::new();
+    for part in parts {
+        hasher.update(part);
+        hasher.update([0]);
+    }
+    let mut bytes: [u8; 16] = hasher.finalize().into();
+    bytes[6] = (bytes[6] & 0x0f) | 0x30;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+/// Chromium stores timestamps as decimal strings; Arc conversions use numbers.
+mod timestamp {
+    use core::fmt;
+    use serde::{Deserializer, Serializer, de};
+    use std::string::ToString;
+
+    pub fn serialize<S: Serializer>(value: &Option<i64>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => serializer.serialize_str(&value.to_string()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<i64>, D::Error> {
+        deserializer.deserialize_any(TimestampVisitor)
+    }
+
+    struct TimestampVisitor;
+
+    impl<'de> de::Visitor<'de> for TimestampVisitor {
+        type Value = Option<i64>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a timestamp as a decimal string or integer")
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+            value.parse().map(Some).map_err(E::custom)
+        }
+
+        fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+            Ok(Some(value))
+        }
+
+        fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+            i64::try_from(value).map(Some).map_err(E::custom)
+        }
+
+        fn visit_f64<E: de::Error>(self, value: f64) -> Result<Self::Value, E> {
+            Ok(Some(value as i64))
+        }
+
+        fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+    }
+}
+
+/// Root folders are stored without the enum wrapper but keep their `"type"` key.
+mod root {
+    use super::BookmarkFolder;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize)]
+    #[serde(tag = "type", rename = "folder")]
+    pub(super) struct Tagged<'a> {
+        #[serde(flatten)]
+        pub(super) folder: &'a BookmarkFolder,
+    }
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<BookmarkFolder>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        value
+            .as_ref()
+            .map(|folder| Tagged { folder })
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<BookmarkFolder>, D::Error> {
+        let mut folder = Option::<BookmarkFolder>::deserialize(deserializer)?;
+        if let Some(folder) = &mut folder {
+            // The tag is not an unknown key; it is written back by `Tagged`:
+            folder.extra.remove("type");
+        }
+        Ok(folder)
+    }
+}
+
+/// Unknown root entries are kept only if they are bookmark folders.
+mod extra_roots {
+    use super::{BookmarkFolder, BookmarkNode};
+    use serde::{Deserialize, Deserializer, Serializer, ser::SerializeMap};
+    use serde_json::Value;
+    use std::collections::BTreeMap;
+    use std::string::String;
+
+    pub fn serialize<S: Serializer>(
+        value: &BTreeMap<String, BookmarkFolder>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(value.len()))?;
+        for (key, folder) in value {
+            map.serialize_entry(key, &super::root::Tagged { folder })?;
+        }
+        map.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<String, BookmarkFolder>, D::Error> {
+        let entries = BTreeMap::<String, Value>::deserialize(deserializer)?;
+        Ok(entries
+            .into_iter()
+            .filter_map(|(key, value)| match serde_json::from_value(value) {
+                Ok(BookmarkNode::Folder(folder)) => Some((key, folder)),
+                _ => None,
+            })
+            .collect())
+    }
+}
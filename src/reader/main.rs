@@ -3,10 +3,19 @@
 #[cfg(not(feature = "std"))]
 compile_error!("asimov-chromium-reader requires the 'std' feature");
 
+#[cfg(feature = "sqlite")]
+use asimov_chromium_module::formats::sqlite;
+#[cfg(feature = "jq")]
+use asimov_chromium_module::jq;
+use asimov_chromium_module::{
+    BookmarksFile, BookmarksTransform, ChecksumStatus, Vocabulary,
+    formats::{Exporter, OutputFormat, markdown, netscape},
+};
 use asimov_module::SysexitsError::{self, *};
 use clap::Parser;
 use clientele::StandardOptions;
-use std::{error::Error, io::Read};
+use miette::{Severity, miette};
+use std::{error::Error, io::Read, path::PathBuf};
 
 /// asimov-chromium-reader
 #[derive(Debug, Parser)]
@@ -15,9 +24,42 @@ struct Options {
     #[clap(flatten)]
     flags: StandardOptions,
 
-    /// The output format.
+    /// The output format (`jsonld`, `json`, `jsonl`, `turtle`, `ntriples`,
+    /// `trig`, `nquads`, `html`, `csv`, `tsv`, `markdown`, `opml`, `atom`,
+    /// `firefox`, `pinboard`, `dot`, or `graphml`).
     #[arg(value_name = "FORMAT", short = 'o', long)]
     output: Option<String>,
+
+    /// The number of most recently added bookmarks in the `atom` feed
+    /// (default: 50).
+    #[arg(value_name = "N", long)]
+    limit: Option<usize>,
+
+    /// Include a node for every bookmark in the `dot` and `graphml` graphs.
+    #[arg(long)]
+    leaves: bool,
+
+    /// Write an Obsidian vault of Markdown notes into this directory instead
+    /// of printing the output.
+    #[arg(value_name = "DIR", long)]
+    vault: Option<PathBuf>,
+
+    /// Write the bookmarks into this SQLite database instead of printing the
+    /// output, updating any rows from earlier runs.
+    #[cfg(feature = "sqlite")]
+    #[arg(value_name = "FILE", long)]
+    sqlite: Option<PathBuf>,
+
+    /// The vocabulary of the JSON-LD and RDF output (`know`, `schema`, or
+    /// `both`).
+    #[arg(value_name = "VOCABULARY", long)]
+    vocabulary: Option<String>,
+
+    /// A jq filter to run instead of the default transform: the name of a
+    /// built-in filter (`bookmarks`, `flatten`) or the path to a jq program.
+    #[cfg(feature = "jq")]
+    #[arg(value_name = "FILTER", long)]
+    filter: Option<String>,
 }
 
 fn main() -> Result<SysexitsError, Box<dyn Error>> {
@@ -46,33 +88,141 @@ fn main() -> Result<SysexitsError, Box<dyn Error>> {
     #[cfg(feature = "tracing")]
     asimov_module::init_tracing_subscriber(&options.flags).expect("failed to initialize logging");
 
-    // Parse the input JSON:
+    // Parse the output format:
+    let format: OutputFormat = match options.output.as_deref().map(str::parse).transpose() {
+        Ok(format) => format.unwrap_or_default(),
+        Err(error) => {
+            eprintln!("{:?}", error);
+            return Ok(EX_USAGE);
+        },
+    };
+    let vocabulary: Vocabulary = match options.vocabulary.as_deref().map(str::parse).transpose() {
+        Ok(vocabulary) => vocabulary.unwrap_or_default(),
+        Err(error) => {
+            eprintln!("{:?}", error);
+            return Ok(EX_USAGE);
+        },
+    };
+    if options.vocabulary.is_some() && !format.uses_transform() {
+        eprintln!(
+            "{:?}",
+            miette!("The --vocabulary option requires a JSON-LD or RDF output format")
+        );
+        return Ok(EX_USAGE);
+    }
+    #[cfg(feature = "jq")]
+    if options.vocabulary.is_some() && options.filter.is_some() {
+        eprintln!(
+            "{:?}",
+            miette!("The --vocabulary option cannot be combined with --filter")
+        );
+        return Ok(EX_USAGE);
+    }
+    #[cfg(feature = "jq")]
+    if options.filter.is_some() && !format.uses_transform() {
+        eprintln!(
+            "{:?}",
+            miette!("The --filter option requires a JSON-LD or RDF output format")
+        );
+        return Ok(EX_USAGE);
+    }
+    if options.vault.is_some() && options.output.is_some() && format != OutputFormat::Markdown {
+        eprintln!(
+            "{:?}",
+            miette!("The --vault option requires the markdown output format")
+        );
+        return Ok(EX_USAGE);
+    }
+    #[cfg(feature = "sqlite")]
+    if options.sqlite.is_some() && (options.output.is_some() || options.vault.is_some()) {
+        eprintln!(
+            "{:?}",
+            miette!("The --sqlite option cannot be combined with --output or --vault")
+        );
+        return Ok(EX_USAGE);
+    }
+
+    // Read the input:
     let mut buffer = String::new();
     std::io::stdin().lock().read_to_string(&mut buffer)?;
-    let mut input: serde_json::Value = serde_json::from_str(&buffer)?;
 
-    // Convert Arc sidebar format to Chromium format
-    if let Some(sidebar) = input.get("sidebar") {
-        if let Some(_containers) = sidebar.get("containers") {
-            // Extract profile from input JSON or use "Default"
-            let profile = input
-                .get("profile")
-                .and_then(|v| v.as_str().map(|s| s.to_string()))
-                .unwrap_or_else(|| "Default".to_string());
-
-            input = asimov_chromium_module::specialized::arc::convert_arc_bookmarks_to_chromium(
-                input,
-                Some(profile.as_str()),
-            )?;
-        }
+    let bookmarks: BookmarksFile = if buffer
+        .trim_start_matches(['\u{FEFF}', ' ', '\t', '\r', '\n'])
+        .starts_with('<')
+    {
+        // Parse Netscape bookmark HTML, as exported by browsers:
+        netscape::from_html(&buffer)?
+    } else {
+        read_json(serde_json::from_str(&buffer)?)?
+    };
+
+    // Report bookmarks files that fail checksum verification:
+    if let ChecksumStatus::Mismatch { expected, actual } = bookmarks.checksum_status() {
+        eprintln!(
+            "{:?}",
+            miette!(
+                severity = Severity::Warning,
+                "Bookmarks checksum mismatch (expected {expected}, computed {actual}): the file may be corrupted or hand-edited"
+            )
+        );
     }
 
-    // Transform JSON to JSON-LD:
-    let transform = asimov_chromium_module::BookmarksTransform::new()?;
-    let output = transform.execute(input)?;
+    // Prepare the JSON-LD transform:
+    #[cfg(feature = "jq")]
+    let transform = match &options.filter {
+        Some(filter) => BookmarksTransform::with_jq(&jq::resolve(filter)?)?,
+        None => BookmarksTransform::new()?.with_vocabulary(vocabulary),
+    };
+    #[cfg(not(feature = "jq"))]
+    let transform = BookmarksTransform::new()?.with_vocabulary(vocabulary);
 
-    // Serialize the output JSON-LD:
-    println!("{}", output);
+    let profiles = [bookmarks.into()];
+
+    // Write the Obsidian vault, if requested:
+    if let Some(directory) = &options.vault {
+        markdown::write_vault(&profiles, directory)?;
+        return Ok(EX_OK);
+    }
+
+    // Write the SQLite catalog, if requested:
+    #[cfg(feature = "sqlite")]
+    if let Some(path) = &options.sqlite {
+        sqlite::write_catalog(&profiles, path)?;
+        return Ok(EX_OK);
+    }
+
+    // Serialize the output:
+    let mut exporter = Exporter::new(format).with_transform(transform);
+    if let Some(limit) = options.limit {
+        exporter = exporter.with_limit(limit);
+    }
+    if options.leaves {
+        exporter = exporter.with_leaves(true);
+    }
+    print!("{}", exporter.export(&profiles)?);
 
     Ok(EX_OK)
 }
+
+/// Parses Chromium bookmarks JSON, converting Arc's sidebar format if needed.
+fn read_json(input: serde_json::Value) -> Result<BookmarksFile, Box<dyn Error>> {
+    // Convert Arc sidebar format to Chromium format
+    if let Some(sidebar) = input.get("sidebar")
+        && let Some(_containers) = sidebar.get("containers")
+    {
+        // Extract profile from input JSON or use "Default"
+        let profile = input
+            .get("profile")
+            .and_then(|v| v.as_str().map(|s| s.to_string()))
+            .unwrap_or_else(|| "Default".to_string());
+
+        Ok(
+            asimov_chromium_module::specialized::arc::convert_arc_bookmarks_to_chromium(
+                input,
+                Some(profile.as_str()),
+            )?,
+        )
+    } else {
+        Ok(serde_json::from_value(input)?)
+    }
+}
@@ -1,5 +1,8 @@
 // This is free and unencumbered software released into the public domain.
 
+use crate::{
+    BOOKMARK_BAR_GUID, BookmarkFolder, BookmarkNode, BookmarkUrl, BookmarksFile, OTHER_GUID, Roots,
+};
 use miette::Result;
 use serde_json::Value;
 use std::collections::HashMap;
@@ -20,40 +23,33 @@ pub struct ArcBookmark {
 fn discover_pinned_container_id(arc_data: &Value) -> Option<String> {
     let mut parent_id_counts: HashMap<String, usize> = HashMap::new();
 
-    if let Some(sidebar) = arc_data.get("sidebar") {
-        if let Some(containers) = sidebar.get("containers") {
-            if let Some(containers_array) = containers.as_array() {
-                for container in containers_array {
-                    if let Some(items) = container.get("items") {
-                        if let Some(items_array) = items.as_array() {
-                            for item in items_array {
-                                if let Some(parent_id) =
-                                    item.get("parentID").and_then(|pid| pid.as_str())
-                                {
-                                    if parent_id != "null" {
-                                        if let Some(data) = item.get("data") {
-                                            if let Some(tab) = data.get("tab") {
-                                                let has_saved_url = tab
-                                                    .get("savedURL")
-                                                    .and_then(|u| u.as_str())
-                                                    .map(|u| !u.is_empty())
-                                                    .unwrap_or(false);
-                                                let has_saved_title = tab
-                                                    .get("savedTitle")
-                                                    .and_then(|t| t.as_str())
-                                                    .map(|t| !t.is_empty() && t != "null")
-                                                    .unwrap_or(false);
-
-                                                if has_saved_url && has_saved_title {
-                                                    *parent_id_counts
-                                                        .entry(parent_id.to_string())
-                                                        .or_insert(0) += 1;
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
+    if let Some(sidebar) = arc_data.get("sidebar")
+        && let Some(containers) = sidebar.get("containers")
+        && let Some(containers_array) = containers.as_array()
+    {
+        for container in containers_array {
+            if let Some(items) = container.get("items")
+                && let Some(items_array) = items.as_array()
+            {
+                for item in items_array {
+                    if let Some(parent_id) = item.get("parentID").and_then(|pid| pid.as_str())
+                        && parent_id != "null"
+                        && let Some(data) = item.get("data")
+                        && let Some(tab) = data.get("tab")
+                    {
+                        let has_saved_url = tab
+                            .get("savedURL")
+                            .and_then(|u| u.as_str())
+                            .map(|u| !u.is_empty())
+                            .unwrap_or(false);
+                        let has_saved_title = tab
+                            .get("savedTitle")
+                            .and_then(|t| t.as_str())
+                            .map(|t| !t.is_empty() && t != "null")
+                            .unwrap_or(false);
+
+                        if has_saved_url && has_saved_title {
+                            *parent_id_counts.entry(parent_id.to_string()).or_insert(0) += 1;
                         }
                     }
                 }
@@ -80,61 +76,33 @@ fn discover_pinned_container_id(arc_data: &Value) -> Option<String> {
 fn discover_profile_containers(arc_data: &Value) -> HashMap<String, String> {
     let mut profile_containers: HashMap<String, String> = HashMap::new();
 
-    if let Some(sidebar) = arc_data.get("sidebar") {
-        if let Some(containers) = sidebar.get("containers") {
-            if let Some(containers_array) = containers.as_array() {
-                for container in containers_array {
-                    if let Some(items) = container.get("items") {
-                        if let Some(items_array) = items.as_array() {
-                            for item in items_array {
-                                if let Some(data) = item.get("data") {
-                                    if let Some(item_container) = data.get("itemContainer") {
-                                        if let Some(container_type) =
-                                            item_container.get("containerType")
-                                        {
-                                            if let Some(top_apps) = container_type.get("topApps") {
-                                                if let Some(top_apps_data) = top_apps.get("_0") {
-                                                    if let Some(custom) =
-                                                        top_apps_data.get("custom")
-                                                    {
-                                                        if let Some(custom_data) = custom.get("_0")
-                                                        {
-                                                            if let Some(directory_basename) =
-                                                                custom_data.get("directoryBasename")
-                                                            {
-                                                                if let Some(profile_name) =
-                                                                    directory_basename.as_str()
-                                                                {
-                                                                    if let Some(id) = item
-                                                                        .get("id")
-                                                                        .and_then(|i| i.as_str())
-                                                                    {
-                                                                        profile_containers.insert(
-                                                                            profile_name
-                                                                                .to_string(),
-                                                                            id.to_string(),
-                                                                        );
-                                                                    }
-                                                                }
-                                                            }
-                                                        }
-                                                    }
-                                                    if top_apps_data.get("default").is_some() {
-                                                        if let Some(id) =
-                                                            item.get("id").and_then(|i| i.as_str())
-                                                        {
-                                                            profile_containers.insert(
-                                                                "Default".to_string(),
-                                                                id.to_string(),
-                                                            );
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
+    if let Some(sidebar) = arc_data.get("sidebar")
+        && let Some(containers) = sidebar.get("containers")
+        && let Some(containers_array) = containers.as_array()
+    {
+        for container in containers_array {
+            if let Some(items) = container.get("items")
+                && let Some(items_array) = items.as_array()
+            {
+                for item in items_array {
+                    if let Some(data) = item.get("data")
+                        && let Some(item_container) = data.get("itemContainer")
+                        && let Some(container_type) = item_container.get("containerType")
+                        && let Some(top_apps) = container_type.get("topApps")
+                        && let Some(top_apps_data) = top_apps.get("_0")
+                    {
+                        if let Some(custom) = top_apps_data.get("custom")
+                            && let Some(custom_data) = custom.get("_0")
+                            && let Some(directory_basename) = custom_data.get("directoryBasename")
+                            && let Some(profile_name) = directory_basename.as_str()
+                            && let Some(id) = item.get("id").and_then(|i| i.as_str())
+                        {
+                            profile_containers.insert(profile_name.to_string(), id.to_string());
+                        }
+                        if top_apps_data.get("default").is_some()
+                            && let Some(id) = item.get("id").and_then(|i| i.as_str())
+                        {
+                            profile_containers.insert("Default".to_string(), id.to_string());
                         }
                     }
                 }
@@ -158,16 +126,16 @@ pub fn extract_arc_bookmarks_for_profile(
     }
 
     // Try new JSON-LD format first
-    if let Some(items) = arc_data.get("items") {
-        if let Some(items_array) = items.as_array() {
-            let mut bookmarks = Vec::new();
-            for item in items_array {
-                if let Some(bookmark) = extract_bookmark_from_jsonld_item(item) {
-                    bookmarks.push(bookmark);
-                }
+    if let Some(items) = arc_data.get("items")
+        && let Some(items_array) = items.as_array()
+    {
+        let mut bookmarks = Vec::new();
+        for item in items_array {
+            if let Some(bookmark) = extract_bookmark_from_jsonld_item(item) {
+                bookmarks.push(bookmark);
             }
-            return Ok(bookmarks);
         }
+        return Ok(bookmarks);
     }
 
     extract_arc_bookmarks_from_sidebar(arc_data, target_profile)
@@ -187,20 +155,19 @@ fn extract_arc_bookmarks_from_sidebar(
             miette::miette!("Could not find any profile containers or pinned bookmarks in Arc data")
         })?;
 
-        if let Some(sidebar) = arc_data.get("sidebar") {
-            if let Some(containers) = sidebar.get("containers") {
-                if let Some(containers_array) = containers.as_array() {
-                    for container in containers_array {
-                        if let Some(items) = container.get("items") {
-                            if let Some(items_array) = items.as_array() {
-                                for item in items_array {
-                                    if let Some(bookmark) =
-                                        extract_bookmark_from_item(item, &pinned_container_id)
-                                    {
-                                        bookmarks.push(bookmark);
-                                    }
-                                }
-                            }
+        if let Some(sidebar) = arc_data.get("sidebar")
+            && let Some(containers) = sidebar.get("containers")
+            && let Some(containers_array) = containers.as_array()
+        {
+            for container in containers_array {
+                if let Some(items) = container.get("items")
+                    && let Some(items_array) = items.as_array()
+                {
+                    for item in items_array {
+                        if let Some(bookmark) =
+                            extract_bookmark_from_item(item, &pinned_container_id)
+                        {
+                            bookmarks.push(bookmark);
                         }
                     }
                 }
@@ -209,30 +176,25 @@ fn extract_arc_bookmarks_from_sidebar(
     } else {
         if let Some(target_profile_name) = target_profile {
             if let Some(container_id) = profile_containers.get(target_profile_name) {
-                if let Some(sidebar) = arc_data.get("sidebar") {
-                    if let Some(containers) = sidebar.get("containers") {
-                        if let Some(containers_array) = containers.as_array() {
-                            for container in containers_array {
-                                if let Some(items) = container.get("items") {
-                                    if let Some(items_array) = items.as_array() {
-                                        for item in items_array {
-                                            if let Some(parent_id) =
-                                                item.get("parentID").and_then(|pid| pid.as_str())
-                                            {
-                                                if parent_id == container_id {
-                                                    if let Some(bookmark) =
-                                                        extract_bookmark_from_item_with_profile(
-                                                            item,
-                                                            container_id,
-                                                            target_profile_name,
-                                                        )
-                                                    {
-                                                        bookmarks.push(bookmark);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
+                if let Some(sidebar) = arc_data.get("sidebar")
+                    && let Some(containers) = sidebar.get("containers")
+                    && let Some(containers_array) = containers.as_array()
+                {
+                    for container in containers_array {
+                        if let Some(items) = container.get("items")
+                            && let Some(items_array) = items.as_array()
+                        {
+                            for item in items_array {
+                                if let Some(parent_id) =
+                                    item.get("parentID").and_then(|pid| pid.as_str())
+                                    && parent_id == container_id
+                                    && let Some(bookmark) = extract_bookmark_from_item_with_profile(
+                                        item,
+                                        container_id,
+                                        target_profile_name,
+                                    )
+                                {
+                                    bookmarks.push(bookmark);
                                 }
                             }
                         }
@@ -252,30 +214,25 @@ fn extract_arc_bookmarks_from_sidebar(
         } else {
             // Extract from all profiles
             for (profile_name, container_id) in profile_containers {
-                if let Some(sidebar) = arc_data.get("sidebar") {
-                    if let Some(containers) = sidebar.get("containers") {
-                        if let Some(containers_array) = containers.as_array() {
-                            for container in containers_array {
-                                if let Some(items) = container.get("items") {
-                                    if let Some(items_array) = items.as_array() {
-                                        for item in items_array {
-                                            if let Some(parent_id) =
-                                                item.get("parentID").and_then(|pid| pid.as_str())
-                                            {
-                                                if parent_id == container_id {
-                                                    if let Some(bookmark) =
-                                                        extract_bookmark_from_item_with_profile(
-                                                            item,
-                                                            &container_id,
-                                                            &profile_name,
-                                                        )
-                                                    {
-                                                        bookmarks.push(bookmark);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
+                if let Some(sidebar) = arc_data.get("sidebar")
+                    && let Some(containers) = sidebar.get("containers")
+                    && let Some(containers_array) = containers.as_array()
+                {
+                    for container in containers_array {
+                        if let Some(items) = container.get("items")
+                            && let Some(items_array) = items.as_array()
+                        {
+                            for item in items_array {
+                                if let Some(parent_id) =
+                                    item.get("parentID").and_then(|pid| pid.as_str())
+                                    && parent_id == container_id
+                                    && let Some(bookmark) = extract_bookmark_from_item_with_profile(
+                                        item,
+                                        &container_id,
+                                        &profile_name,
+                                    )
+                                {
+                                    bookmarks.push(bookmark);
                                 }
                             }
                         }
@@ -353,7 +310,10 @@ fn extract_bookmark_from_item_with_profile(
 }
 
 /// Converts Arc bookmarks to Chromium format for browser import
-pub fn convert_arc_bookmarks_to_chromium(arc_data: Value, profile: Option<&str>) -> Result<Value> {
+pub fn convert_arc_bookmarks_to_chromium(
+    arc_data: Value,
+    profile: Option<&str>,
+) -> Result<BookmarksFile> {
     let bookmarks = extract_arc_bookmarks_for_profile(&arc_data, profile)?;
     let mut counter = 0;
     let mut chromium_bookmarks = Vec::new();
@@ -370,31 +330,32 @@ pub fn convert_arc_bookmarks_to_chromium(arc_data: Value, profile: Option<&str>)
             ((now.as_secs() + 11644473600) * 1000000) as i64
         };
 
-        let chromium_bookmark = serde_json::json!({
-            "guid": format!("arc-{}-{}", date_added, counter),
-            "name": bookmark.title,
-            "url": bookmark.url,
-            "type": "url",
-            "date_added": date_added,
-        });
-
-        chromium_bookmarks.push(chromium_bookmark);
+        chromium_bookmarks.push(BookmarkNode::Url(BookmarkUrl {
+            guid: Some(format!("arc-{}-{}", date_added, counter)),
+            name: bookmark.title,
+            url: bookmark.url,
+            date_added: Some(date_added),
+            ..Default::default()
+        }));
     }
 
-    let result = serde_json::json!({
-        "roots": {
-            "bookmark_bar": {
-                "children": chromium_bookmarks,
-                "type": "folder"
-            },
-            "other": {
-                "children": [],
-                "type": "folder"
-            }
-        }
-    });
-
-    Ok(result)
+    Ok(BookmarksFile {
+        roots: Roots {
+            bookmark_bar: Some(BookmarkFolder {
+                guid: Some(BOOKMARK_BAR_GUID.to_string()),
+                name: "Bookmarks bar".to_string(),
+                children: chromium_bookmarks,
+                ..Default::default()
+            }),
+            other: Some(BookmarkFolder {
+                guid: Some(OTHER_GUID.to_string()),
+                name: "Other bookmarks".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        ..Default::default()
+    })
 }
 
 /// Converts Arc's CFAbsoluteTime to Chromium timestamp format
@@ -0,0 +1,151 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::{BookmarkFolder, BookmarkNode, BookmarksFile};
+use serde::{Deserialize, Serialize};
+
+/// Where Chrome stores a bookmark.
+///
+/// Recent Chrome versions keep bookmarks synced with the signed-in account in
+/// a separate `AccountBookmarks` file next to the local `Bookmarks` file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BookmarkStorage {
+    /// Stored only on this device, in `Bookmarks`.
+    Local,
+    /// Stored in the signed-in account, in `AccountBookmarks`.
+    Account,
+    /// Present in both files.
+    Both,
+}
+
+impl BookmarkStorage {
+    /// The name of the storage as serialized, such as `local`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BookmarkStorage::Local => "local",
+            BookmarkStorage::Account => "account",
+            BookmarkStorage::Both => "both",
+        }
+    }
+}
+
+impl BookmarksFile {
+    /// Tags every node in this file with the given storage.
+    pub fn set_storage(&mut self, storage: BookmarkStorage) {
+        for root in self.roots_mut() {
+            root.set_storage(storage);
+        }
+    }
+
+    /// Merges another file, typically `AccountBookmarks`, into this one.
+    ///
+    /// Folders with the same name under the same parent folder are merged,
+    /// and a bookmark is not added to a folder that already had one with the
+    /// same URL. Duplicates within either file are kept. Nodes found in both
+    /// files are tagged [`BookmarkStorage::Both`].
+    pub fn merge(&mut self, other: BookmarksFile) {
+        let roots = &mut self.roots;
+        let other = other.roots;
+        merge_root(&mut roots.bookmark_bar, other.bookmark_bar);
+        merge_root(&mut roots.other, other.other);
+        merge_root(&mut roots.synced, other.synced);
+        for (key, folder) in other.extra {
+            match roots.extra.get_mut(&key) {
+                Some(root) => root.merge(folder),
+                None => {
+                    roots.extra.insert(key, folder);
+                },
+            }
+        }
+        // The stored checksum no longer describes the merged contents:
+        self.checksum = None;
+    }
+
+    fn roots_mut(&mut self) -> impl Iterator<Item = &mut BookmarkFolder> {
+        let roots = &mut self.roots;
+        [
+            roots.bookmark_bar.as_mut(),
+            roots.other.as_mut(),
+            roots.synced.as_mut(),
+        ]
+        .into_iter()
+        .flatten()
+        .chain(roots.extra.values_mut())
+    }
+}
+
+impl BookmarkFolder {
+    /// Tags this folder and all its descendants with the given storage.
+    pub fn set_storage(&mut self, storage: BookmarkStorage) {
+        self.storage = Some(storage);
+        for child in &mut self.children {
+            match child {
+                BookmarkNode::Url(url) => url.storage = Some(storage),
+                BookmarkNode::Folder(folder) => folder.set_storage(storage),
+            }
+        }
+    }
+
+    fn merge(&mut self, other: BookmarkFolder) {
+        self.storage = merge_storage(self.storage, other.storage);
+        // Only match against this folder's own children, not added ones:
+        let original = self.children.len();
+        for child in other.children {
+            match child {
+                BookmarkNode::Url(url) => {
+                    let existing =
+                        self.children[..original]
+                            .iter_mut()
+                            .find_map(|node| match node {
+                                BookmarkNode::Url(existing) if existing.url == url.url => {
+                                    Some(existing)
+                                },
+                                _ => None,
+                            });
+                    match existing {
+                        Some(existing) => {
+                            existing.storage = merge_storage(existing.storage, url.storage);
+                            existing.date_last_used =
+                                existing.date_last_used.max(url.date_last_used);
+                        },
+                        None => self.children.push(BookmarkNode::Url(url)),
+                    }
+                },
+                BookmarkNode::Folder(folder) => {
+                    let existing =
+                        self.children[..original]
+                            .iter_mut()
+                            .find_map(|node| match node {
+                                BookmarkNode::Folder(existing) if existing.name == folder.name => {
+                                    Some(existing)
+                                },
+                                _ => None,
+                            });
+                    match existing {
+                        Some(existing) => existing.merge(folder),
+                        None => self.children.push(BookmarkNode::Folder(folder)),
+                    }
+                },
+            }
+        }
+    }
+}
+
+fn merge_root(root: &mut Option<BookmarkFolder>, other: Option<BookmarkFolder>) {
+    match (root.as_mut(), other) {
+        (Some(root), Some(other)) => root.merge(other),
+        (None, Some(other)) => *root = Some(other),
+        (_, None) => {},
+    }
+}
+
+fn merge_storage(
+    storage: Option<BookmarkStorage>,
+    other: Option<BookmarkStorage>,
+) -> Option<BookmarkStorage> {
+    match (storage, other) {
+        (Some(storage), Some(other)) if storage == other => Some(storage),
+        (Some(_), Some(_)) => Some(BookmarkStorage::Both),
+        (storage, other) => storage.or(other),
+    }
+}
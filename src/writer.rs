@@ -0,0 +1,539 @@
+// This is free and unencumbered software released into the public domain.
+
+//! Writing of Chromium `Bookmarks` files.
+//!
+//! Bookmarks can come from KNOW JSON-LD, as produced by
+//! [`BookmarksTransform`](crate::BookmarksTransform), or from the typed tree.
+//! Before writing, every node is given a fresh sequential id, missing or
+//! duplicate GUIDs are replaced, missing dates are set to the current time,
+//! and the checksum is recomputed, so that Chromium accepts the file as is.
+
+use crate::{
+    BOOKMARK_BAR_GUID, BookmarkFolder, BookmarkNode, BookmarkUrl, BookmarksFile, OTHER_GUID, Roots,
+    SYNCED_GUID, name_based_guid, unix_micros_to_webkit,
+};
+use miette::{IntoDiagnostic, Result, WrapErr, miette};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::format;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+/// The file format version Chromium writes.
+pub const VERSION: u32 = 1;
+
+/// Serializes bookmarks as a Chromium `Bookmarks` file, after preparing them
+/// with [`prepare`] at the current time.
+pub fn to_json(bookmarks: &BookmarksFile) -> Result<String> {
+    serde_json::to_string_pretty(&prepare(bookmarks, current_time())).into_diagnostic()
+}
+
+/// Writes bookmarks to a Chromium `Bookmarks` file without risking a
+/// partially written file: they are written to a temporary file in the same
+/// directory, which is flushed to disk and then renamed over the target. An
+/// existing file is first copied to its `.bak` backup.
+pub fn write_file(path: &Path, bookmarks: &BookmarksFile) -> Result<()> {
+    let output = to_json(bookmarks)?;
+    let temporary = path.with_extension("tmp");
+    let result = write_synced(&temporary, output.as_bytes());
+    if result.is_err() {
+        let _ = fs::remove_file(&temporary);
+    }
+    result.wrap_err_with(|| format!("Failed to write {}", temporary.display()))?;
+
+    if path.is_file() {
+        let backup = path.with_extension("bak");
+        fs::copy(path, &backup)
+            .into_diagnostic()
+            .wrap_err_with(|| {
+                format!(
+                    "Failed to back up {} to {}",
+                    path.display(),
+                    backup.display()
+                )
+            })?;
+    }
+    fs::rename(&temporary, path)
+        .into_diagnostic()
+        .wrap_err_with(|| format!("Failed to replace {}", path.display()))?;
+
+    // Make the rename itself durable:
+    #[cfg(unix)]
+    if let Some(directory) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        File::open(directory)
+            .and_then(|directory| directory.sync_all())
+            .into_diagnostic()?;
+    }
+    Ok(())
+}
+
+fn write_synced(path: &Path, contents: &[u8]) -> Result<()> {
+    let mut file = File::create(path).into_diagnostic()?;
+    file.write_all(contents).into_diagnostic()?;
+    file.sync_all().into_diagnostic()
+}
+
+/// Returns a copy of the bookmarks that Chromium will load without repairs.
+///
+/// The bookmark bar, other and mobile roots are created if missing and get
+/// their well-known GUIDs. Nodes are numbered sequentially, roots first and
+/// then depth-first in document order. Missing, malformed or duplicate GUIDs
+/// are replaced by name-based ones, and missing creation dates are set to
+/// `now` (a WebKit timestamp). Sync metadata and storage tags are dropped,
+/// as they would no longer match.
+pub fn prepare(bookmarks: &BookmarksFile, now: i64) -> BookmarksFile {
+    let mut roots = bookmarks.roots.clone();
+    let permanent = [
+        (&mut roots.bookmark_bar, "Bookmarks bar", BOOKMARK_BAR_GUID),
+        (&mut roots.other, "Other bookmarks", OTHER_GUID),
+        (&mut roots.synced, "Mobile bookmarks", SYNCED_GUID),
+    ];
+    let mut preparer = Preparer {
+        next_id: 1,
+        guids: BTreeSet::new(),
+        now,
+    };
+    for (root, name, guid) in permanent {
+        let root = root.get_or_insert_with(|| BookmarkFolder {
+            name: name.to_string(),
+            ..Default::default()
+        });
+        root.guid = Some(guid.to_string());
+    }
+
+    let mut folders: Vec<(&str, &mut BookmarkFolder)> = [
+        ("bookmark_bar", roots.bookmark_bar.as_mut()),
+        ("other", roots.other.as_mut()),
+        ("synced", roots.synced.as_mut()),
+    ]
+    .into_iter()
+    .filter_map(|(key, folder)| folder.map(|folder| (key, folder)))
+    .chain(
+        roots
+            .extra
+            .iter_mut()
+            .map(|(key, folder)| (key.as_str(), folder)),
+    )
+    .collect();
+
+    // Roots are numbered before their descendants, as in a new profile:
+    for (key, root) in folders.iter_mut() {
+        root.id = Some(preparer.next_id());
+        if !preparer.claim_guid(&root.guid) {
+            root.guid = Some(preparer.derive_guid(&[key]));
+        }
+        root.date_added.get_or_insert(now);
+        root.storage = None;
+    }
+    for (key, root) in folders {
+        preparer.prepare_children(root, key);
+    }
+
+    let mut output = BookmarksFile {
+        checksum: None,
+        roots,
+        sync_metadata: None,
+        version: Some(VERSION),
+        extra: bookmarks.extra.clone(),
+    };
+    output.checksum = Some(output.compute_checksum());
+    output
+}
+
+struct Preparer {
+    next_id: u64,
+    guids: BTreeSet<String>,
+    now: i64,
+}
+
+impl Preparer {
+    fn next_id(&mut self) -> String {
+        let id = self.next_id;
+        self.next_id += 1;
+        id.to_string()
+    }
+
+    /// Registers a well-formed GUID, unless it is missing or already taken.
+    fn claim_guid(&mut self, guid: &Option<String>) -> bool {
+        match guid {
+            Some(guid) if is_guid(guid) => self.guids.insert(guid.to_lowercase()),
+            _ => false,
+        }
+    }
+
+    fn derive_guid(&mut self, parts: &[&str]) -> String {
+        let guid = name_based_guid(parts);
+        self.guids.insert(guid.clone());
+        guid
+    }
+
+    fn prepare_children(&mut self, folder: &mut BookmarkFolder, path: &str) {
+        for (position, child) in folder.children.iter_mut().enumerate() {
+            let path = format!("{path}/{position}");
+            match child {
+                BookmarkNode::Url(url) => {
+                    url.id = Some(self.next_id());
+                    url.guid = match self.claim_guid(&url.guid) {
+                        true => url.guid.as_deref().map(str::to_lowercase),
+                        false => Some(self.derive_guid(&[&path, &url.name, &url.url])),
+                    };
+                    url.date_added.get_or_insert(self.now);
+                    url.storage = None;
+                },
+                BookmarkNode::Folder(folder) => {
+                    folder.id = Some(self.next_id());
+                    folder.guid = match self.claim_guid(&folder.guid) {
+                        true => folder.guid.as_deref().map(str::to_lowercase),
+                        false => Some(self.derive_guid(&[&path, &folder.name])),
+                    };
+                    folder.date_added.get_or_insert(self.now);
+                    folder.storage = None;
+                    self.prepare_children(folder, &path);
+                },
+            }
+        }
+    }
+}
+
+/// Checks for the `8-4-4-4-12` hexadecimal form that Chromium requires.
+fn is_guid(guid: &str) -> bool {
+    guid.len() == 36
+        && guid.char_indices().all(|(index, c)| match index {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+fn insert_root(roots: &mut Roots, key: &str, mut folder: BookmarkFolder) {
+    let root = match key {
+        "bookmark_bar" => &mut roots.bookmark_bar,
+        "other" => &mut roots.other,
+        "synced" => &mut roots.synced,
+        _ => {
+            match roots.extra.get_mut(key) {
+                Some(root) => root.children.append(&mut folder.children),
+                None => {
+                    roots.extra.insert(key.to_string(), folder);
+                },
+            }
+            return;
+        },
+    };
+    match root {
+        Some(root) => root.children.append(&mut folder.children),
+        None => *root = Some(folder),
+    }
+}
+
+/// Reads bookmarks from KNOW or schema.org JSON-LD, as produced by
+/// [`BookmarksTransform`](crate::BookmarksTransform).
+///
+/// Folders without a parent folder become the roots named by their `root`
+/// property. The children of a folder are its `members`, or otherwise the
+/// nodes that name it as their `folder`, ordered by their `position` either
+/// way. Nodes that belong to no folder are added to their root, or to "Other
+/// bookmarks".
+/// The document may be a single JSON-LD document, a `@graph`, or an array.
+pub fn from_jsonld(document: &Value) -> Result<BookmarksFile> {
+    let mut nodes = Vec::new();
+    collect_nodes(document, &mut nodes);
+    let graph = Graph::new(nodes);
+    if graph.nodes.is_empty() {
+        return Err(miette!(
+            "No KNOW bookmarks or bookmark folders found in the JSON-LD"
+        ));
+    }
+
+    let mut roots = Roots::default();
+    let mut used = Vec::from_iter(graph.nodes.iter().map(|_| false));
+    // Root folders come first, so that their subtrees claim their members:
+    let (root_folders, others): (Vec<_>, Vec<_>) = (0..graph.nodes.len())
+        .filter(|&index| graph.parent(index).is_none())
+        .partition(|&index| {
+            graph.nodes[index].kind == Kind::Folder && graph.nodes[index].root.is_some()
+        });
+    for index in root_folders {
+        if let Some(BookmarkNode::Folder(folder)) = graph.build(index, &mut used) {
+            insert_root(
+                &mut roots,
+                graph.nodes[index].root.unwrap_or("other"),
+                folder,
+            );
+        }
+    }
+    for index in others {
+        if used[index] {
+            continue;
+        }
+        if let Some(node) = graph.build(index, &mut used) {
+            let key = graph.nodes[index].root.unwrap_or("other");
+            let folder = BookmarkFolder {
+                name: default_root_name(key).to_string(),
+                children: Vec::from([node]),
+                ..Default::default()
+            };
+            insert_root(&mut roots, key, folder);
+        }
+    }
+
+    Ok(BookmarksFile {
+        roots,
+        ..Default::default()
+    })
+}
+
+fn default_root_name(key: &str) -> &str {
+    match key {
+        "bookmark_bar" => "Bookmarks bar",
+        "other" => "Other bookmarks",
+        "synced" => "Mobile bookmarks",
+        key => key,
+    }
+}
+
+/// Gathers the node objects of JSON-LD documents, `@graph`s and arrays,
+/// including the members nested in folders.
+fn collect_nodes<'a>(value: &'a Value, nodes: &mut Vec<&'a Map<String, Value>>) {
+    match value {
+        Value::Array(values) => {
+            for value in values {
+                collect_nodes(value, nodes);
+            }
+        },
+        Value::Object(object) => match object.get("items").or_else(|| object.get("@graph")) {
+            Some(items) => collect_nodes(items, nodes),
+            None => {
+                nodes.push(object);
+                if let Some(members @ Value::Array(_)) = property(object, ("members", "hasPart")) {
+                    collect_nodes(members, nodes);
+                }
+            },
+        },
+        _ => {},
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Folder,
+    Url,
+}
+
+struct Node<'a> {
+    kind: Kind,
+    object: &'a Map<String, Value>,
+    id: Option<&'a str>,
+    root: Option<&'a str>,
+}
+
+struct Graph<'a> {
+    nodes: Vec<Node<'a>>,
+    index: BTreeMap<&'a str, usize>,
+    /// The nodes that name each folder as theirs, with their position.
+    contents: BTreeMap<usize, Vec<(u64, usize)>>,
+}
+
+impl<'a> Graph<'a> {
+    fn new(objects: Vec<&'a Map<String, Value>>) -> Self {
+        let nodes: Vec<Node> = objects
+            .into_iter()
+            .filter_map(|object| {
+                let kind = match node_type(object)? {
+                    "BookmarkFolder" | "Collection" => Kind::Folder,
+                    "Bookmark" | "WebPage" => Kind::Url,
+                    _ => return None,
+                };
+                Some(Node {
+                    kind,
+                    object,
+                    id: object.get("@id").and_then(Value::as_str),
+                    root: object.get("root").and_then(text),
+                })
+            })
+            .collect();
+
+        let mut index = BTreeMap::new();
+        for (position, node) in nodes.iter().enumerate() {
+            if let Some(id) = node.id {
+                index.entry(id).or_insert(position);
+            }
+        }
+
+        let mut graph = Graph {
+            nodes,
+            index,
+            contents: BTreeMap::new(),
+        };
+        for position in 0..graph.nodes.len() {
+            if let Some(parent) = graph.parent(position) {
+                let order = graph.position(position);
+                graph
+                    .contents
+                    .entry(parent)
+                    .or_default()
+                    .push((order, position));
+            }
+        }
+        for contents in graph.contents.values_mut() {
+            contents.sort();
+        }
+        graph
+    }
+
+    /// Returns the folder a node names as its parent, if it is in the graph.
+    fn parent(&self, index: usize) -> Option<usize> {
+        let folder =
+            property(self.nodes[index].object, ("folder", "isPartOf")).and_then(reference)?;
+        let parent = *self.index.get(folder)?;
+        (self.nodes[parent].kind == Kind::Folder && parent != index).then_some(parent)
+    }
+
+    /// The position of a node within its folder, if it has one.
+    fn position(&self, index: usize) -> u64 {
+        property(self.nodes[index].object, ("position", "schema:position"))
+            .and_then(Value::as_u64)
+            .unwrap_or(u64::MAX)
+    }
+
+    fn children(&self, index: usize) -> Vec<usize> {
+        match property(self.nodes[index].object, ("members", "hasPart")) {
+            Some(Value::Array(members)) => {
+                // Members are a set, so their order is given by `position`:
+                let mut children: Vec<usize> = members
+                    .iter()
+                    .filter_map(|member| self.index.get(reference(member)?).copied())
+                    .collect();
+                children.sort_by_key(|&child| self.position(child));
+                children
+            },
+            _ => self
+                .contents
+                .get(&index)
+                .map(|contents| contents.iter().map(|&(_, child)| child).collect())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Builds the subtree of a node, skipping nodes already placed elsewhere.
+    fn build(&self, index: usize, used: &mut [bool]) -> Option<BookmarkNode> {
+        if used[index] {
+            return None;
+        }
+        used[index] = true;
+        let node = &self.nodes[index];
+        let object = node.object;
+        let guid = node
+            .id
+            .and_then(|id| id.strip_prefix("urn:uuid:"))
+            .filter(|guid| !guid.is_empty())
+            .map(str::to_string);
+        let name = property(object, ("title", "name"))
+            .and_then(text)
+            .unwrap_or_default()
+            .to_string();
+        let meta_info = annotations(object);
+        match node.kind {
+            Kind::Url => Some(BookmarkNode::Url(BookmarkUrl {
+                guid,
+                name,
+                url: property(object, ("link", "url"))
+                    .and_then(reference)?
+                    .to_string(),
+                date_added: date(object, ("created", "dateCreated")),
+                date_last_used: date(object, ("lastUsed", "lastUsed")),
+                meta_info,
+                ..Default::default()
+            })),
+            Kind::Folder => Some(BookmarkNode::Folder(BookmarkFolder {
+                guid,
+                name,
+                date_added: date(object, ("created", "dateCreated")),
+                date_last_used: date(object, ("lastUsed", "lastUsed")),
+                date_modified: date(object, ("modified", "dateModified")),
+                meta_info,
+                children: self
+                    .children(index)
+                    .into_iter()
+                    .filter_map(|child| self.build(child, used))
+                    .collect(),
+                ..Default::default()
+            })),
+        }
+    }
+}
+
+/// Returns the KNOW class name of a node, e.g., `Bookmark`.
+fn node_type(object: &Map<String, Value>) -> Option<&str> {
+    let types = match object.get("@type")? {
+        Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+        value => Vec::from([value.as_str()?]),
+    };
+    types.into_iter().find_map(|name| {
+        [
+            "know:",
+            "https://know.dev/",
+            "schema:",
+            "https://schema.org/",
+        ]
+        .into_iter()
+        .find_map(|prefix| name.strip_prefix(prefix))
+    })
+}
+
+/// Returns a string value, which may be a value object or in a set.
+fn text(value: &Value) -> Option<&str> {
+    match value {
+        Value::String(text) => Some(text),
+        Value::Object(object) => object.get("@value").and_then(text),
+        Value::Array(values) => values.first().and_then(text),
+        _ => None,
+    }
+}
+
+/// Returns an IRI value, which may be a node reference.
+fn reference(value: &Value) -> Option<&str> {
+    match value {
+        Value::Object(object) => object.get("@id").and_then(Value::as_str),
+        value => text(value),
+    }
+}
+
+/// Returns a property by its KNOW term, or else by its schema.org term.
+fn property<'a>(object: &'a Map<String, Value>, (know, schema): (&str, &str)) -> Option<&'a Value> {
+    object.get(know).or_else(|| object.get(schema))
+}
+
+/// Parses an ISO 8601 date-time property into a WebKit timestamp.
+fn date(object: &Map<String, Value>, terms: (&str, &str)) -> Option<i64> {
+    let timestamp: jiff::Timestamp = property(object, terms).and_then(text)?.parse().ok()?;
+    unix_micros_to_webkit(timestamp.as_microsecond())
+}
+
+fn annotations(object: &Map<String, Value>) -> BTreeMap<String, String> {
+    let Some(Value::Array(annotations)) = object.get("annotations") else {
+        return BTreeMap::new();
+    };
+    annotations
+        .iter()
+        .filter_map(|annotation| {
+            let key = annotation.get("key").and_then(text)?;
+            let value = annotation.get("value").and_then(text)?;
+            Some((key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Returns the current time as a WebKit timestamp.
+fn current_time() -> i64 {
+    let micros = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_micros() as i64)
+        .unwrap_or_default();
+    unix_micros_to_webkit(micros).unwrap_or_default()
+}
@@ -0,0 +1,90 @@
+// This is free and unencumbered software released into the public domain.
+
+#[cfg(not(feature = "std"))]
+compile_error!("asimov-chromium-writer requires the 'std' feature");
+
+use asimov_chromium_module::{BookmarksFile, formats::netscape, writer};
+use asimov_module::SysexitsError::{self, *};
+use clap::Parser;
+use clientele::StandardOptions;
+use miette::WrapErr;
+use serde_json::Value;
+use std::{error::Error, io::Read, path::PathBuf};
+
+/// asimov-chromium-writer
+#[derive(Debug, Parser)]
+#[command(arg_required_else_help = false)]
+struct Options {
+    #[clap(flatten)]
+    flags: StandardOptions,
+
+    /// The `Bookmarks` file to write (default: standard output), keeping the
+    /// previous file as `Bookmarks.bak`. Quit the browser first, or it will
+    /// overwrite the file.
+    #[arg(value_name = "FILE")]
+    output: Option<PathBuf>,
+}
+
+fn main() -> Result<SysexitsError, Box<dyn Error>> {
+    // Load environment variables from `.env`:
+    asimov_module::dotenv().ok();
+
+    // Expand wildcards and @argfiles:
+    let args = asimov_module::args_os()?;
+
+    // Parse command-line options:
+    let options = Options::parse_from(args);
+
+    // Handle the `--version` flag:
+    if options.flags.version {
+        println!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+        return Ok(EX_OK);
+    }
+
+    // Handle the `--license` flag:
+    if options.flags.license {
+        print!("{}", include_str!("../../UNLICENSE"));
+        return Ok(EX_OK);
+    }
+
+    // Configure logging & tracing:
+    #[cfg(feature = "tracing")]
+    asimov_module::init_tracing_subscriber(&options.flags).expect("failed to initialize logging");
+
+    // Read the input:
+    let mut buffer = String::new();
+    std::io::stdin().lock().read_to_string(&mut buffer)?;
+
+    let bookmarks: BookmarksFile = if buffer
+        .trim_start_matches(['\u{FEFF}', ' ', '\t', '\r', '\n'])
+        .starts_with('<')
+    {
+        // Parse Netscape bookmark HTML, as exported by browsers:
+        netscape::from_html(&buffer)?
+    } else {
+        // Merge one or more JSON documents, as printed for several profiles:
+        let mut bookmarks = BookmarksFile::default();
+        for document in serde_json::Deserializer::from_str(&buffer).into_iter::<Value>() {
+            bookmarks.merge(read_json(document?)?);
+        }
+        bookmarks
+    };
+
+    // Serialize the output:
+    match &options.output {
+        Some(path) => writer::write_file(path, &bookmarks)
+            .wrap_err_with(|| format!("Failed to write bookmarks file: {}", path.display()))?,
+        None => println!("{}", writer::to_json(&bookmarks)?),
+    }
+
+    Ok(EX_OK)
+}
+
+/// Parses either the Chromium bookmarks JSON tree or KNOW JSON-LD.
+fn read_json(input: Value) -> Result<BookmarksFile, Box<dyn Error>> {
+    if input.get("roots").is_some() {
+        Ok(serde_json::from_value(input)?)
+    } else {
+        Ok(writer::from_jsonld(&input)?)
+    }
+}
@@ -0,0 +1,45 @@
+// This is free and unencumbered software released into the public domain.
+
+mod common;
+
+use asimov_chromium_module::{BookmarkNode, formats::atom};
+use common::fixture;
+
+#[test]
+fn entries_are_the_newest_bookmarks() {
+    let atom = atom::to_atom(&[fixture("Bookmarks").into()], 2);
+    assert_eq!(atom.matches("<entry>").count(), 2);
+    assert!(atom.contains("<updated>2025-05-16T03:50:23.456789Z</updated>\n  <author>"));
+    assert!(
+        atom.contains("<link rel=\"alternate\" href=\"https://en.wikipedia.org/wiki/Main_Page\"/>")
+    );
+    assert!(atom.contains("<title>Wikipedia &lt;Free Encyclopedia&gt; &amp; more</title>"));
+}
+
+#[test]
+fn bookmarks_without_a_valid_date_are_left_out() {
+    let mut bookmarks = fixture("Bookmarks");
+    let folder = bookmarks.roots.bookmark_bar.as_mut().unwrap();
+    let BookmarkNode::Url(url) = &mut folder.children[0] else {
+        panic!("expected a bookmark");
+    };
+    url.date_added = Some(i64::MAX);
+    let BookmarkNode::Folder(folder) = &mut folder.children[1] else {
+        panic!("expected a folder");
+    };
+    let BookmarkNode::Url(url) = &mut folder.children[0] else {
+        panic!("expected a bookmark");
+    };
+    url.date_added = Some(0);
+
+    let atom = atom::to_atom(&[bookmarks.clone().into()], atom::DEFAULT_LIMIT);
+    assert_eq!(
+        atom.matches("<entry>").count(),
+        bookmarks.urls().count() - 2
+    );
+    assert!(atom.contains("<updated>2025-05-16T03:50:23.456789Z</updated>\n  <author>"));
+    assert!(!atom.contains("<updated></updated>"));
+    assert!(!atom.contains("<published></published>"));
+    assert!(!atom.contains("https://asimov.sh/"));
+    assert!(!atom.contains("https://doc.rust-lang.org/book/"));
+}
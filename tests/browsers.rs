@@ -0,0 +1,153 @@
+// This is free and unencumbered software released into the public domain.
+
+use asimov_chromium_module::{
+    BookmarkStorage, ChecksumStatus,
+    browsers::{FetchOptions, read_bookmarks_source},
+};
+use std::path::{Path, PathBuf};
+
+/// Creates an empty profile directory with the given files.
+fn profile(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let directory =
+        std::env::temp_dir().join(format!("asimov-chromium-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    std::fs::create_dir_all(&directory).unwrap();
+    for (file, contents) in files {
+        std::fs::write(directory.join(file), contents).unwrap();
+    }
+    directory
+}
+
+fn valid() -> String {
+    let path = format!("{}/tests/fixtures/Bookmarks", env!("CARGO_MANIFEST_DIR"));
+    std::fs::read_to_string(path).unwrap()
+}
+
+fn truncated() -> String {
+    valid()[..100].to_string()
+}
+
+/// A hand-edited file whose checksum no longer matches.
+fn edited() -> String {
+    valid().replace("\"ASIMOV\"", "\"ASIMOV!\"")
+}
+
+fn read(
+    directory: &Path,
+    backup: bool,
+) -> miette::Result<(PathBuf, Option<String>, ChecksumStatus)> {
+    let options = FetchOptions { backup };
+    let path = directory.join("Bookmarks");
+    let (source, _) = read_bookmarks_source(&path, "Default", BookmarkStorage::Local, &options)?;
+    Ok((source.path, source.fallback_reason, source.checksum))
+}
+
+#[test]
+fn intact_files_are_read_as_is() {
+    let directory = profile(
+        "intact",
+        &[("Bookmarks", &valid()), ("Bookmarks.bak", &edited())],
+    );
+    let (path, reason, checksum) = read(&directory, false).unwrap();
+    assert_eq!(path, directory.join("Bookmarks"));
+    assert_eq!(reason, None);
+    assert_eq!(checksum, ChecksumStatus::Valid);
+}
+
+#[test]
+fn missing_files_fall_back_to_the_backup() {
+    let directory = profile("missing", &[("Bookmarks.bak", &valid())]);
+    let (path, reason, checksum) = read(&directory, false).unwrap();
+    assert_eq!(path, directory.join("Bookmarks.bak"));
+    assert_eq!(
+        reason.unwrap(),
+        format!(
+            "Bookmarks file not found at {}",
+            directory.join("Bookmarks").display()
+        )
+    );
+    assert_eq!(checksum, ChecksumStatus::Valid);
+}
+
+#[test]
+fn truncated_files_fall_back_to_the_backup() {
+    let directory = profile(
+        "truncated",
+        &[("Bookmarks", &truncated()), ("Bookmarks.bak", &valid())],
+    );
+    let (path, reason, _) = read(&directory, false).unwrap();
+    assert_eq!(path, directory.join("Bookmarks.bak"));
+    assert_eq!(
+        reason.unwrap(),
+        format!(
+            "Failed to parse bookmarks JSON from {}",
+            directory.join("Bookmarks").display()
+        )
+    );
+}
+
+#[test]
+fn checksum_mismatches_fall_back_to_an_intact_backup() {
+    let directory = profile(
+        "mismatch",
+        &[("Bookmarks", &edited()), ("Bookmarks.bak", &valid())],
+    );
+    let (path, reason, checksum) = read(&directory, false).unwrap();
+    assert_eq!(path, directory.join("Bookmarks.bak"));
+    assert_eq!(
+        reason.unwrap(),
+        format!(
+            "Bookmarks checksum mismatch in {}",
+            directory.join("Bookmarks").display()
+        )
+    );
+    assert_eq!(checksum, ChecksumStatus::Valid);
+
+    // Without an intact backup, the edited file is read and reported:
+    let directory = profile(
+        "mismatch-both",
+        &[("Bookmarks", &edited()), ("Bookmarks.bak", &edited())],
+    );
+    let (path, reason, checksum) = read(&directory, false).unwrap();
+    assert_eq!(path, directory.join("Bookmarks"));
+    assert_eq!(reason, None);
+    assert!(matches!(checksum, ChecksumStatus::Mismatch { .. }));
+}
+
+#[test]
+fn unusable_backups_are_reported_with_the_primary_file() {
+    let directory = profile(
+        "unusable",
+        &[("Bookmarks", &truncated()), ("Bookmarks.bak", &truncated())],
+    );
+    let error = read(&directory, false).unwrap_err();
+    let reasons: Vec<String> = error.chain().map(|cause| cause.to_string()).collect();
+    assert_eq!(
+        reasons[..2],
+        [
+            format!(
+                "The backup file could not be used either: Failed to parse bookmarks JSON from {}",
+                directory.join("Bookmarks.bak").display()
+            ),
+            format!(
+                "Failed to parse bookmarks JSON from {}",
+                directory.join("Bookmarks").display()
+            ),
+        ]
+    );
+
+    let directory = profile("none", &[]);
+    assert!(read(&directory, false).is_err());
+}
+
+#[test]
+fn backups_can_be_read_on_purpose() {
+    let directory = profile(
+        "backup",
+        &[("Bookmarks", &valid()), ("Bookmarks.bak", &edited())],
+    );
+    let (path, reason, checksum) = read(&directory, true).unwrap();
+    assert_eq!(path, directory.join("Bookmarks.bak"));
+    assert_eq!(reason, None);
+    assert!(matches!(checksum, ChecksumStatus::Mismatch { .. }));
+}
@@ -0,0 +1,80 @@
+// This is free and unencumbered software released into the public domain.
+
+mod common;
+
+use asimov_chromium_module::{BookmarkNode, BookmarksFile, ChecksumStatus};
+use common::fixture;
+use serde_json::json;
+
+#[test]
+fn fixture_checksums_are_valid() {
+    assert_eq!(
+        fixture("Bookmarks").checksum_status(),
+        ChecksumStatus::Valid
+    );
+    assert_eq!(
+        fixture("AccountBookmarks").checksum_status(),
+        ChecksumStatus::Valid
+    );
+}
+
+#[test]
+fn titles_are_hashed_as_utf16() {
+    // The checksum Chromium stores for this file, computed independently:
+    let bookmarks: BookmarksFile = serde_json::from_value(json!({
+        "checksum": "b65890c207418a60b8aa6713679fc075",
+        "roots": {
+            "bookmark_bar": {
+                "id": "1",
+                "name": "Bookmarks bar",
+                "type": "folder",
+                "children": [{
+                    "id": "4",
+                    "name": "Café — 日本語 😀",
+                    "type": "url",
+                    "url": "https://example.org/",
+                }],
+            },
+            "other": {"id": "2", "name": "Other bookmarks", "type": "folder", "children": []},
+            "synced": {"id": "3", "name": "Mobile bookmarks", "type": "folder", "children": []},
+        },
+        "version": 1,
+    }))
+    .unwrap();
+    assert_eq!(bookmarks.checksum_status(), ChecksumStatus::Valid);
+}
+
+#[test]
+fn edited_titles_and_urls_are_a_mismatch() {
+    let original = fixture("Bookmarks");
+    let expected = original.checksum.clone().unwrap();
+
+    let mut edited = original.clone();
+    let folder = edited.roots.other.as_mut().unwrap();
+    let BookmarkNode::Url(url) = &mut folder.children[0] else {
+        panic!("expected a bookmark");
+    };
+    url.name.push('!');
+    assert!(matches!(
+        edited.checksum_status(),
+        ChecksumStatus::Mismatch { expected: stored, actual } if stored == expected && actual != expected
+    ));
+
+    let mut edited = original.clone();
+    let folder = edited.roots.other.as_mut().unwrap();
+    let BookmarkNode::Url(url) = &mut folder.children[0] else {
+        panic!("expected a bookmark");
+    };
+    url.url.push_str("#edited");
+    assert!(matches!(
+        edited.checksum_status(),
+        ChecksumStatus::Mismatch { .. }
+    ));
+}
+
+#[test]
+fn absent_checksums_are_missing() {
+    let mut bookmarks = fixture("Bookmarks");
+    bookmarks.checksum = None;
+    assert_eq!(bookmarks.checksum_status(), ChecksumStatus::Missing);
+}
@@ -0,0 +1,10 @@
+// This is free and unencumbered software released into the public domain.
+
+use asimov_chromium_module::BookmarksFile;
+
+/// Reads a bookmarks file from `tests/fixtures`.
+pub fn fixture(name: &str) -> BookmarksFile {
+    let path = format!("{}/tests/fixtures/{name}", env!("CARGO_MANIFEST_DIR"));
+    let input = std::fs::read_to_string(path).unwrap();
+    serde_json::from_str(&input).unwrap()
+}
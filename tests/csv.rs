@@ -0,0 +1,49 @@
+// This is free and unencumbered software released into the public domain.
+
+mod common;
+
+use asimov_chromium_module::{BookmarkNode, formats::csv};
+use common::fixture;
+
+#[test]
+fn csv_rows_are_quoted_as_in_rfc_4180() {
+    let bookmarks = fixture("Bookmarks");
+    let output = csv::to_csv(&[bookmarks.clone().into()]);
+    assert!(output.ends_with("\r\n"));
+    let rows: Vec<&str> = output.split_terminator("\r\n").collect();
+    assert_eq!(rows.len(), bookmarks.urls().count() + 1);
+    assert_eq!(rows[0], csv::COLUMNS.join(","));
+    assert_eq!(
+        rows[3],
+        ",,bookmark_bar,Rust/Crates,\"serde — \"\"serialization\"\" framework\",\
+         https://serde.rs/,2025-05-16T03:27:03.378910Z,,e5f6a7b8-c9d0-4e1f-8a2b-3c4d5e6f7a81",
+    );
+    assert_eq!(
+        rows[4],
+        ",,other,,Wikipedia <Free Encyclopedia> & more,https://en.wikipedia.org/wiki/Main_Page,\
+         2025-05-16T03:33:32.345678Z,,f6a7b8c9-d0e1-4f2a-9b3c-4d5e6f7a8b92",
+    );
+}
+
+#[test]
+fn tsv_fields_with_tabs_or_line_breaks_are_quoted() {
+    let mut bookmarks = fixture("Bookmarks");
+    let folder = bookmarks.roots.bookmark_bar.as_mut().unwrap();
+    let BookmarkNode::Url(url) = &mut folder.children[0] else {
+        panic!("expected a bookmark");
+    };
+    url.name = "ASIMOV\tProtocol\nmodules".into();
+
+    let output = csv::to_tsv(&[bookmarks.into()]);
+    let rows: Vec<&str> = output.split_terminator("\r\n").collect();
+    assert_eq!(rows[0], csv::COLUMNS.join("\t"));
+    assert_eq!(
+        rows[1],
+        "\t\tbookmark_bar\t\t\"ASIMOV\tProtocol\nmodules\"\thttps://asimov.sh/\t\
+         2025-05-16T03:23:36.783612Z\t2025-05-18T01:30:12.334918Z\t\
+         3a6b6ed2-8f0c-4b8f-9f1e-1d2c0f5e6a71",
+    );
+    // Only the delimiter, quotes and line breaks need quotes:
+    assert!(rows[4].contains("\tWikipedia <Free Encyclopedia> & more\t"));
+    assert!(rows[3].contains("\t\"serde — \"\"serialization\"\" framework\"\t"));
+}
@@ -0,0 +1,141 @@
+// This is free and unencumbered software released into the public domain.
+
+mod common;
+
+use asimov_chromium_module::{BookmarkFolder, browsers::ProfileBookmarks, formats::firefox};
+use common::fixture;
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+fn backup(profiles: &[ProfileBookmarks]) -> Value {
+    serde_json::from_str(&firefox::to_firefox_json(profiles).unwrap()).unwrap()
+}
+
+/// Returns the Firefox root with the given title.
+fn root<'a>(backup: &'a Value, title: &str) -> &'a Value {
+    backup["children"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|root| root["title"] == title)
+        .unwrap_or_else(|| panic!("no root {title}"))
+}
+
+fn titles(folder: &Value) -> Vec<&str> {
+    folder["children"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|child| child["title"].as_str().unwrap())
+        .collect()
+}
+
+#[test]
+fn chromium_roots_map_to_firefox_roots() {
+    let backup = backup(&[fixture("Bookmarks").into()]);
+    assert_eq!(backup["guid"], "root________");
+    assert_eq!(backup["root"], "placesRoot");
+    assert_eq!(
+        backup["children"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|root| (
+                root["guid"].as_str().unwrap(),
+                root["root"].as_str().unwrap()
+            ))
+            .collect::<Vec<_>>(),
+        [
+            ("menu________", "bookmarksMenuFolder"),
+            ("toolbar_____", "toolbarFolder"),
+            ("tags________", "tagsFolder"),
+            ("unfiled_____", "unfiledBookmarksFolder"),
+            ("mobile______", "mobileFolder"),
+        ]
+    );
+    assert_eq!(titles(root(&backup, "toolbar")), ["ASIMOV", "Rust"]);
+    assert_eq!(
+        titles(root(&backup, "unfiled")),
+        ["Wikipedia <Free Encyclopedia> & more"]
+    );
+    assert_eq!(titles(root(&backup, "mobile")), ["Café Müller — Über uns"]);
+    assert_eq!(root(&backup, "menu")["children"], Value::Array(Vec::new()));
+}
+
+#[test]
+fn bookmarks_and_folders_have_their_type_codes() {
+    let backup = backup(&[fixture("Bookmarks").into()]);
+    let toolbar = root(&backup, "toolbar");
+    assert_eq!(toolbar["dateAdded"], 1747365794012345_i64);
+
+    let bookmark = &toolbar["children"][0];
+    assert_eq!(bookmark["typeCode"], 1);
+    assert_eq!(bookmark["type"], "text/x-moz-place");
+    assert_eq!(bookmark["uri"], "https://asimov.sh/");
+    assert_eq!(bookmark["index"], 0);
+    assert_eq!(bookmark["dateAdded"], 1747365816783612_i64);
+    assert_eq!(bookmark.get("children"), None);
+
+    let folder = &toolbar["children"][1];
+    assert_eq!(folder["typeCode"], 2);
+    assert_eq!(folder["type"], "text/x-moz-place-container");
+    assert_eq!(folder["index"], 1);
+    assert_eq!(folder.get("uri"), None);
+    assert_eq!(folder.get("root"), None);
+    assert_eq!(titles(folder), ["The Rust Programming Language", "Crates"]);
+
+    // GUIDs are 12 URL-safe characters, derived from the Chromium GUIDs:
+    let guid = bookmark["guid"].as_str().unwrap();
+    assert_eq!(guid.len(), 12);
+    assert!(
+        guid.bytes()
+            .all(|c| c.is_ascii_alphanumeric() || c == b'-' || c == b'_')
+    );
+    assert_eq!(guid, "XoYmAPCsxhge");
+
+    // Every node has its own ID: the places root, the five Firefox roots,
+    // and the five bookmarks and two folders of the fixture.
+    let mut ids = BTreeSet::new();
+    let mut stack = Vec::from([&backup]);
+    while let Some(node) = stack.pop() {
+        assert!(ids.insert(node["id"].as_u64().unwrap()));
+        stack.extend(node["children"].as_array().into_iter().flatten());
+    }
+    assert_eq!(ids.len(), 1 + 5 + 7);
+}
+
+#[test]
+fn other_roots_become_unfiled_folders() {
+    let mut bookmarks = fixture("Bookmarks");
+    bookmarks.roots.extra.insert(
+        "workspaces".into(),
+        BookmarkFolder {
+            name: "Workspaces".into(),
+            ..Default::default()
+        },
+    );
+    let backup = backup(&[bookmarks.into()]);
+    let unfiled = root(&backup, "unfiled");
+    assert_eq!(
+        titles(unfiled),
+        ["Wikipedia <Free Encyclopedia> & more", "Workspaces"]
+    );
+    assert_eq!(unfiled["children"][1]["typeCode"], 2);
+    assert_eq!(unfiled["children"][1]["index"], 1);
+}
+
+#[test]
+fn profiles_get_a_folder_in_each_root() {
+    let profile = |name: &str| ProfileBookmarks {
+        browser: Some("chrome".into()),
+        profile: Some(name.into()),
+        sources: Vec::new(),
+        bookmarks: fixture("Bookmarks"),
+        warnings: Vec::new(),
+    };
+    let backup = backup(&[profile("Default"), profile("Profile 1")]);
+    let toolbar = root(&backup, "toolbar");
+    assert_eq!(titles(toolbar), ["Default (chrome)", "Profile 1 (chrome)"]);
+    assert_eq!(titles(&toolbar["children"][1]), ["ASIMOV", "Rust"]);
+    assert_eq!(toolbar.get("dateAdded"), None);
+}
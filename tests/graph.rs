@@ -0,0 +1,74 @@
+// This is free and unencumbered software released into the public domain.
+
+mod common;
+
+use asimov_chromium_module::{BookmarkNode, formats::graph};
+use common::fixture;
+
+#[test]
+fn dot_graphs_show_the_folder_tree() {
+    let dot = graph::to_dot(&[fixture("Bookmarks").into()], false);
+    assert!(dot.starts_with("digraph bookmarks {\n  rankdir=LR;\n  node [shape=folder];\n"));
+    assert!(dot.contains("  n0 [label=\"Bookmarks\\n5 bookmarks\", shape=box3d];\n"));
+    assert!(dot.contains("  n1 [label=\"Bookmarks bar\\n1 bookmark, 3 in total\", style=bold];\n"));
+    assert!(dot.contains("  n2 [label=\"Rust\\n1 bookmark, 2 in total\"];\n"));
+    assert!(dot.contains("  n3 [label=\"Crates\\n1 bookmark\"];\n"));
+    assert!(!dot.contains("shape=note"));
+
+    let edges: Vec<&str> = dot.lines().filter(|line| line.contains("->")).collect();
+    assert_eq!(
+        edges,
+        [
+            "  n0 -> n1;",
+            "  n1 -> n2;",
+            "  n2 -> n3;",
+            "  n0 -> n4;",
+            "  n0 -> n5;"
+        ]
+    );
+}
+
+#[test]
+fn dot_labels_are_escaped() {
+    let mut bookmarks = fixture("Bookmarks");
+    let folder = bookmarks.roots.bookmark_bar.as_mut().unwrap();
+    let BookmarkNode::Folder(folder) = &mut folder.children[1] else {
+        panic!("expected a folder");
+    };
+    folder.name = "Rust \"lang\" C:\\docs\r\nbook".into();
+
+    let dot = graph::to_dot(&[bookmarks.into()], true);
+    assert!(dot.contains(
+        "  n3 [label=\"Rust \\\"lang\\\" C:\\\\docs\\nbook\\n1 bookmark, 2 in total\"];\n"
+    ));
+    assert!(dot.contains(
+        "  n6 [label=\"serde — \\\"serialization\\\" framework\", shape=note, \
+         URL=\"https://serde.rs/\"];\n"
+    ));
+    assert_eq!(dot.matches("shape=note").count(), 5);
+}
+
+#[test]
+fn graphml_data_is_xml_escaped() {
+    let graphml = graph::to_graphml(&[fixture("Bookmarks").into()], true);
+    assert!(graphml.contains("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n"));
+    let lines: Vec<&str> = graphml.lines().map(str::trim).collect();
+    let label = lines
+        .iter()
+        .position(|line| line.contains("Wikipedia"))
+        .unwrap();
+    assert_eq!(
+        lines[label - 1..=label + 1],
+        [
+            "<data key=\"kind\">bookmark</data>",
+            "<data key=\"label\">Wikipedia &lt;Free Encyclopedia&gt; &amp; more</data>",
+            "<data key=\"url\">https://en.wikipedia.org/wiki/Main_Page</data>",
+        ]
+    );
+    assert!(
+        graphml.contains("<data key=\"label\">serde — &quot;serialization&quot; framework</data>")
+    );
+    assert!(graphml.contains("<data key=\"url\">https://example.com/caf%C3%A9?q=a&amp;b=c</data>"));
+    assert_eq!(graphml.matches("<node ").count(), 11);
+    assert_eq!(graphml.matches("<edge ").count(), 10);
+}
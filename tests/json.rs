@@ -0,0 +1,35 @@
+// This is free and unencumbered software released into the public domain.
+
+mod common;
+
+use asimov_chromium_module::{
+    BookmarkStorage,
+    browsers::ProfileBookmarks,
+    formats::{Exporter, OutputFormat},
+};
+use common::fixture;
+use serde_json::Value;
+
+#[test]
+fn json_output_is_the_raw_chromium_file() {
+    let path = format!("{}/tests/fixtures/Bookmarks", env!("CARGO_MANIFEST_DIR"));
+    let original: Value = serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+
+    let mut bookmarks = fixture("Bookmarks");
+    bookmarks.set_storage(BookmarkStorage::Local);
+    let profiles = [ProfileBookmarks::from(bookmarks)];
+    let output = Exporter::new(OutputFormat::Json).export(&profiles).unwrap();
+    // Unknown keys are kept, and no keys are added:
+    let output: Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(output, original);
+    assert_eq!(
+        output["roots"]["bookmark_bar"]["children"][0]["unsynced_meta_info"]["last_visited_desktop"],
+        "13392005412334918"
+    );
+
+    let lines = Exporter::new(OutputFormat::JsonLines)
+        .export(&profiles)
+        .unwrap();
+    let first: Value = serde_json::from_str(lines.lines().next().unwrap()).unwrap();
+    assert_eq!(first, original["roots"]["bookmark_bar"]["children"][0]);
+}
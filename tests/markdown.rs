@@ -0,0 +1,105 @@
+// This is free and unencumbered software released into the public domain.
+
+mod common;
+
+use asimov_chromium_module::{browsers::ProfileBookmarks, formats::markdown};
+use common::fixture;
+use std::path::PathBuf;
+
+#[test]
+fn folders_become_nested_headings() {
+    let output = markdown::to_markdown(&[fixture("Bookmarks").into()]);
+    let headings: Vec<&str> = output
+        .lines()
+        .filter(|line| line.starts_with('#'))
+        .collect();
+    assert_eq!(
+        headings,
+        [
+            "# Bookmarks",
+            "## Bookmarks bar",
+            "### Rust",
+            "#### Crates",
+            "## Other bookmarks",
+            "## Mobile bookmarks",
+        ]
+    );
+    assert!(
+        output
+            .contains("- [ASIMOV](https://asimov.sh/) — added 2025-05-16, last used 2025-05-18\n")
+    );
+    assert!(output.contains(
+        "- [Wikipedia \\<Free Encyclopedia\\> & more](https://en.wikipedia.org/wiki/Main_Page)"
+    ));
+}
+
+#[test]
+fn vault_mirrors_the_folder_tree() {
+    let notes = markdown::vault_notes(&[fixture("Bookmarks").into()]);
+    let mut paths: Vec<PathBuf> = notes.iter().map(|(path, _)| path.clone()).collect();
+    paths.sort();
+    assert_eq!(
+        paths,
+        [
+            "Bookmarks bar/ASIMOV.md",
+            "Bookmarks bar/Bookmarks bar.md",
+            "Bookmarks bar/Rust/Crates/Crates.md",
+            "Bookmarks bar/Rust/Crates/serde — -serialization- framework.md",
+            "Bookmarks bar/Rust/Rust.md",
+            "Bookmarks bar/Rust/The Rust Programming Language.md",
+            "Mobile bookmarks/Café Müller — Über uns.md",
+            "Mobile bookmarks/Mobile bookmarks.md",
+            "Other bookmarks/Other bookmarks.md",
+            "Other bookmarks/Wikipedia -Free Encyclopedia- & more.md",
+        ]
+        .map(PathBuf::from)
+    );
+
+    let note = |path: &str| {
+        let path = PathBuf::from(path);
+        &notes.iter().find(|(note, _)| *note == path).unwrap().1
+    };
+    assert_eq!(
+        note("Bookmarks bar/Rust/Rust.md"),
+        "---\n\
+         title: \"Rust\"\n\
+         created: 2025-05-16T03:24:40.000000Z\n\
+         modified: 2025-05-16T03:27:03.378910Z\n\
+         tags:\n  - Bookmarks-bar\n\
+         guid: b6d9f1a4-2c3e-4d5f-8a7b-9c0d1e2f3a4b\n\
+         ---\n\n\
+         # Rust\n\n\
+         - [[Bookmarks bar/Rust/The Rust Programming Language|The Rust Programming Language]]\n\
+         - [[Bookmarks bar/Rust/Crates/Crates|Crates]]\n"
+    );
+    let serde = note("Bookmarks bar/Rust/Crates/serde — -serialization- framework.md");
+    assert!(serde.starts_with("---\ntitle: \"serde — \\\"serialization\\\" framework\"\n"));
+    assert!(serde.contains("tags:\n  - Bookmarks-bar/Rust/Crates\n"));
+}
+
+#[test]
+fn vault_gives_each_profile_a_directory() {
+    let profile = |name: &str| ProfileBookmarks {
+        browser: Some("chrome".into()),
+        profile: Some(name.into()),
+        sources: Vec::new(),
+        bookmarks: fixture("Bookmarks"),
+        warnings: Vec::new(),
+    };
+    let directory =
+        std::env::temp_dir().join(format!("asimov-chromium-vault-{}", std::process::id()));
+    let paths =
+        markdown::write_vault(&[profile("Default"), profile("Profile 1")], &directory).unwrap();
+    assert_eq!(paths.len(), 20);
+    assert!(
+        directory
+            .join("Default (chrome)/Bookmarks bar/Rust/Rust.md")
+            .is_file()
+    );
+    assert!(
+        directory
+            .join("Profile 1 (chrome)/Other bookmarks/Other bookmarks.md")
+            .is_file()
+    );
+    std::fs::remove_dir_all(&directory).unwrap();
+}
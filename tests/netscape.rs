@@ -0,0 +1,84 @@
+// This is free and unencumbered software released into the public domain.
+
+mod common;
+
+use asimov_chromium_module::{
+    BookmarkNode, BookmarksFile, formats::netscape, webkit_to_unix_micros,
+};
+use common::fixture;
+
+/// The tree structure of a bookmarks file: root, depth, title and URL.
+fn outline(bookmarks: &BookmarksFile) -> Vec<(String, usize, String, Option<String>)> {
+    fn walk(
+        outline: &mut Vec<(String, usize, String, Option<String>)>,
+        root: &str,
+        depth: usize,
+        nodes: &[BookmarkNode],
+    ) {
+        for node in nodes {
+            let url = node.as_url().map(|url| url.url.clone());
+            outline.push((root.into(), depth, node.name().into(), url));
+            if let Some(folder) = node.as_folder() {
+                walk(outline, root, depth + 1, &folder.children);
+            }
+        }
+    }
+
+    let mut outline = Vec::new();
+    for (root, folder) in bookmarks.roots.iter() {
+        outline.push((root.into(), 0, folder.name.clone(), None));
+        walk(&mut outline, root, 1, &folder.children);
+    }
+    outline
+}
+
+#[test]
+fn html_round_trip_preserves_the_tree() {
+    let bookmarks = fixture("Bookmarks");
+    let html = netscape::to_html(&[bookmarks.clone().into()]);
+    let imported = netscape::from_html(&html).unwrap();
+    assert_eq!(outline(&imported), outline(&bookmarks));
+    assert_eq!(netscape::to_html(&[imported.into()]), html);
+}
+
+#[test]
+fn non_ascii_titles_are_imported() {
+    let html = "<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<DL><p>
+    <DT><H3>Ünïcode</H3>
+    <DL><p>
+        <DT><A HREF=\"https://example.org/\">日本語</A>
+        <DT><A HREF=\"https://example.org/emoji\">😀 < 1</A>
+    </DL><p>
+</DL><p>
+";
+    let imported = netscape::from_html(html).unwrap();
+    let folder = imported.roots.other.as_ref().unwrap().children[0]
+        .as_folder()
+        .unwrap();
+    assert_eq!(folder.name, "Ünïcode");
+    let titles: Vec<&str> = folder.children.iter().map(|node| node.name()).collect();
+    assert_eq!(titles, ["日本語", "😀 < 1"]);
+
+    // Our own export of these titles imports the same way:
+    let exported = netscape::to_html(&[imported.clone().into()]);
+    assert_eq!(
+        outline(&netscape::from_html(&exported).unwrap()),
+        outline(&imported)
+    );
+}
+
+#[test]
+fn out_of_range_dates_are_dropped() {
+    let html = "<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<DL><p>
+    <DT><A HREF=\"https://example.org/\" ADD_DATE=\"9223370000000\" LAST_MODIFIED=\"-9223372036854\">Far future</A>
+    <DT><A HREF=\"https://example.org/now\" ADD_DATE=\"1700000000\">Now</A>
+</DL><p>
+";
+    let imported = netscape::from_html(html).unwrap();
+    let children = &imported.roots.other.as_ref().unwrap().children;
+    assert_eq!(children[0].date_added(), None);
+    assert_eq!(children[1].date_added(), Some(13_344_473_600_000_000));
+    assert_eq!(webkit_to_unix_micros(i64::MIN + 1), None);
+}
@@ -0,0 +1,67 @@
+// This is free and unencumbered software released into the public domain.
+
+mod common;
+
+use asimov_chromium_module::{BookmarkNode, browsers::ProfileBookmarks, formats::opml};
+use common::fixture;
+
+#[test]
+fn folders_become_nested_outlines() {
+    let output = opml::to_opml(&[fixture("Bookmarks").into()]);
+    assert!(
+        output.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<opml version=\"2.0\">\n")
+    );
+    let lines: Vec<&str> = output.lines().collect();
+    let rust = lines
+        .iter()
+        .position(|line| line.trim_start().starts_with("<outline text=\"Rust\""))
+        .unwrap();
+    assert_eq!(
+        lines[rust],
+        "      <outline text=\"Rust\" created=\"Fri, 16 May 2025 03:24:40 GMT\">"
+    );
+    assert_eq!(
+        lines[rust + 1],
+        "        <outline text=\"The Rust Programming Language\" type=\"link\" \
+         url=\"https://doc.rust-lang.org/book/\" created=\"Fri, 16 May 2025 03:25:01 GMT\"/>"
+    );
+    assert_eq!(
+        output.matches("<outline").count(),
+        output.matches("</outline>").count() + output.matches("/>").count()
+    );
+}
+
+#[test]
+fn text_and_urls_are_xml_escaped() {
+    let mut bookmarks = fixture("Bookmarks");
+    let folder = bookmarks.roots.bookmark_bar.as_mut().unwrap();
+    let BookmarkNode::Url(url) = &mut folder.children[0] else {
+        panic!("expected a bookmark");
+    };
+    url.name = "It's\nASIMOV\u{7}".into();
+
+    let output = opml::to_opml(&[bookmarks.into()]);
+    assert!(output.contains("<outline text=\"It&#39;s&#10;ASIMOV\" type=\"link\""));
+    assert!(output.contains("<outline text=\"serde — &quot;serialization&quot; framework\""));
+    assert!(output.contains("<outline text=\"Wikipedia &lt;Free Encyclopedia&gt; &amp; more\""));
+    assert!(output.contains("url=\"https://example.com/caf%C3%A9?q=a&amp;b=c\""));
+}
+
+#[test]
+fn profiles_become_top_level_outlines() {
+    let profile = |name: &str| ProfileBookmarks {
+        browser: Some("chrome".into()),
+        profile: Some(name.into()),
+        sources: Vec::new(),
+        bookmarks: fixture("Bookmarks"),
+        warnings: Vec::new(),
+    };
+    let output = opml::to_opml(&[profile("Default"), profile("Profile 1")]);
+    assert!(output.contains("<title>Bookmarks</title>"));
+    assert!(output.contains("\n    <outline text=\"Default (chrome)\">\n"));
+    assert!(output.contains("\n    <outline text=\"Profile 1 (chrome)\">\n"));
+    assert_eq!(output.matches("<outline text=\"Bookmarks bar\"").count(), 2);
+
+    let output = opml::to_opml(&[profile("Default")]);
+    assert!(output.contains("<title>Default (chrome)</title>"));
+}
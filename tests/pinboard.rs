@@ -0,0 +1,50 @@
+// This is free and unencumbered software released into the public domain.
+
+mod common;
+
+use asimov_chromium_module::{BookmarkNode, formats::pinboard};
+use common::fixture;
+use serde_json::{Value, json};
+
+#[test]
+fn bookmarks_become_private_posts() {
+    let bookmarks = fixture("Bookmarks");
+    let output = pinboard::to_pinboard(&[bookmarks.clone().into()]).unwrap();
+    let posts: Vec<Value> = serde_json::from_str(&output).unwrap();
+    assert_eq!(posts.len(), bookmarks.urls().count());
+    assert_eq!(
+        posts[2],
+        json!({
+            "href": "https://serde.rs/",
+            "description": "serde — \"serialization\" framework",
+            "extended": "",
+            // The MD5 digest of the URL:
+            "hash": "3f401e927623c01bb31e0f2441fb3f3e",
+            "time": "2025-05-16T03:27:03Z",
+            "shared": "no",
+            "toread": "no",
+            "tags": "Rust Crates",
+        })
+    );
+    // Bookmarks directly in a root have no tags:
+    assert_eq!(posts[0]["tags"], "");
+}
+
+#[test]
+fn folder_names_become_dashed_tags() {
+    let mut bookmarks = fixture("Bookmarks");
+    let folder = bookmarks.roots.bookmark_bar.as_mut().unwrap();
+    let BookmarkNode::Folder(folder) = &mut folder.children[1] else {
+        panic!("expected a folder");
+    };
+    folder.name = " ".into();
+    let BookmarkNode::Folder(folder) = &mut folder.children[1] else {
+        panic!("expected a folder");
+    };
+    folder.name = "Rust  crates\tand tools".into();
+
+    let output = pinboard::to_pinboard(&[bookmarks.into()]).unwrap();
+    let posts: Vec<Value> = serde_json::from_str(&output).unwrap();
+    assert_eq!(posts[1]["tags"], "");
+    assert_eq!(posts[2]["tags"], "Rust-crates-and-tools");
+}
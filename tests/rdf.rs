@@ -0,0 +1,269 @@
+// This is free and unencumbered software released into the public domain.
+
+mod common;
+
+use asimov_chromium_module::{
+    BOOKMARK_BAR_GUID, BookmarkNode, BookmarkStorage, BookmarksFile, BookmarksTransform,
+    Vocabulary,
+    browsers::ProfileBookmarks,
+    formats::{
+        Exporter, OutputFormat,
+        rdf::{Dataset, Term},
+    },
+};
+use common::fixture;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+
+const RDF_TYPE: &str = "<http://www.w3.org/1999/02/22-rdf-syntax-ns#type>";
+
+fn dataset(document: Value) -> Dataset {
+    let mut dataset = Dataset::new();
+    dataset.insert_jsonld(&document, None).unwrap();
+    dataset
+}
+
+fn context() -> Value {
+    json!({
+        "know": "https://know.dev/",
+        "title": { "@id": "know:title" },
+        "link": { "@id": "know:link", "@type": "@id" },
+        "members": { "@id": "know:member", "@type": "@id", "@container": "@list" },
+    })
+}
+
+#[test]
+fn literals_escape_quotes_and_control_characters() {
+    let dataset = dataset(json!({
+        "@context": context(),
+        "@id": "https://example.org/a",
+        "title": "Say \"hi\"\\\n\r\tCafé 日本語 😀\u{7}",
+    }));
+    assert_eq!(
+        dataset.to_ntriples(),
+        "<https://example.org/a> <https://know.dev/title> \
+         \"Say \\\"hi\\\"\\\\\\n\\r\\tCafé 日本語 😀\\u0007\" .\n",
+    );
+}
+
+#[test]
+fn iris_percent_encode_invalid_characters() {
+    let dataset = dataset(json!({
+        "@context": context(),
+        "@id": "https://example.org/café",
+        "link": "https://example.org/a b\"<c>{d}",
+    }));
+    assert_eq!(
+        dataset.to_ntriples(),
+        "<https://example.org/café> <https://know.dev/link> \
+         <https://example.org/a%20b%22%3Cc%3E%7Bd%7D> .\n",
+    );
+}
+
+#[test]
+fn list_containers_become_rdf_lists() {
+    let dataset = dataset(json!({
+        "@context": context(),
+        "@id": "https://example.org/folder",
+        "members": ["https://example.org/a", "https://example.org/b"],
+    }));
+    assert_eq!(
+        dataset.to_ntriples(),
+        "_:b1 <http://www.w3.org/1999/02/22-rdf-syntax-ns#first> <https://example.org/b> .\n\
+         _:b1 <http://www.w3.org/1999/02/22-rdf-syntax-ns#rest> <http://www.w3.org/1999/02/22-rdf-syntax-ns#nil> .\n\
+         _:b2 <http://www.w3.org/1999/02/22-rdf-syntax-ns#first> <https://example.org/a> .\n\
+         _:b2 <http://www.w3.org/1999/02/22-rdf-syntax-ns#rest> _:b1 .\n\
+         <https://example.org/folder> <https://know.dev/member> _:b2 .\n",
+    );
+}
+
+#[test]
+fn turtle_falls_back_to_full_iris() {
+    let dataset = dataset(json!({
+        "@context": context(),
+        "@id": "know:a/b",
+        "@type": "know:Bookmark",
+        "link": "know:c",
+    }));
+    let turtle = dataset.to_turtle();
+    // Local names with characters outside `PN_LOCAL` are not abbreviated:
+    assert!(
+        turtle.contains("\n<https://know.dev/a/b> a know:Bookmark ;\n    know:link know:c .\n")
+    );
+}
+
+#[test]
+fn documents_without_triples_are_rejected() {
+    let mut dataset = Dataset::new();
+    let document = json!([{ "title": "No context" }]);
+    assert!(dataset.insert_jsonld(&document, None).is_err());
+}
+
+/// Two Chrome profiles, whose root folders have the same GUIDs.
+fn profiles() -> Vec<ProfileBookmarks> {
+    let profile = |name: &str, mut bookmarks: BookmarksFile| {
+        bookmarks.set_storage(BookmarkStorage::Local);
+        ProfileBookmarks {
+            browser: Some("chrome".into()),
+            profile: Some(name.into()),
+            sources: Vec::new(),
+            bookmarks,
+            warnings: Vec::new(),
+        }
+    };
+    let mut account = fixture("AccountBookmarks");
+    let folder = account.roots.bookmark_bar.as_mut().unwrap();
+    folder.guid = Some(BOOKMARK_BAR_GUID.into());
+    let BookmarkNode::Url(url) = &mut folder.children[0] else {
+        panic!("expected a bookmark");
+    };
+    url.guid = None;
+    Vec::from([
+        profile("Default", fixture("Bookmarks")),
+        profile("Profile 1", account),
+    ])
+}
+
+#[test]
+fn profiles_have_their_own_root_folders() {
+    let profiles = profiles();
+    let ntriples = Exporter::new(OutputFormat::NTriples)
+        .export(&profiles)
+        .unwrap();
+    assert!(!ntriples.contains(BOOKMARK_BAR_GUID));
+    assert!(!ntriples.contains("<urn:uuid:>"));
+
+    // Every node is typed once, so no two nodes were merged:
+    let mut types = BTreeMap::new();
+    for line in ntriples.lines().filter(|line| line.contains(RDF_TYPE)) {
+        *types.entry(line.split(' ').next().unwrap()).or_insert(0) += 1;
+    }
+    assert!(types.values().all(|&count| count == 1));
+
+    // Each profile is named by a plain literal and lists its root folders:
+    assert!(ntriples.contains(
+        "<chrome://bookmarks/Default> <http://www.w3.org/2000/01/rdf-schema#label> \"Default\" .\n"
+    ));
+    let members = ntriples
+        .lines()
+        .filter(|line| {
+            line.starts_with(
+                "<chrome://bookmarks/Default> <http://www.w3.org/2000/01/rdf-schema#member>",
+            )
+        })
+        .count();
+    assert_eq!(members, 3);
+    for subject in [
+        "<chrome://bookmarks/Default#bookmark_bar>",
+        "<chrome://bookmarks/Profile%201#bookmark_bar>",
+        "<chrome://bookmarks/Profile%201#bookmark_bar-0>",
+    ] {
+        assert_eq!(types.get(subject), Some(&1), "{subject}");
+    }
+
+    let turtle = Exporter::new(OutputFormat::Turtle)
+        .export(&profiles)
+        .unwrap();
+    assert!(!turtle.contains(", know:BookmarkFolder"));
+    assert_eq!(
+        turtle
+            .matches("<chrome://bookmarks/Default#bookmark_bar> a know:BookmarkFolder ;")
+            .count(),
+        1
+    );
+
+    let jsonld = Exporter::new(OutputFormat::JsonLd)
+        .export(&profiles)
+        .unwrap();
+    let documents: Vec<Value> = jsonld
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(
+        documents[0]["items"][0]["@id"],
+        "chrome://bookmarks/Default#bookmark_bar"
+    );
+    assert_eq!(
+        documents[1]["items"][0]["members"][0]["@id"],
+        "chrome://bookmarks/Profile%201#bookmark_bar-0"
+    );
+}
+
+#[test]
+fn blank_nodes_are_local_to_their_document() {
+    let mut dataset = Dataset::new();
+    for title in ["First", "Second"] {
+        let document = json!({
+            "@context": context(),
+            "@id": "_:root",
+            "title": title,
+        });
+        dataset.insert_jsonld(&document, None).unwrap();
+    }
+    let subjects: Vec<&Term> = dataset.quads.iter().map(|quad| &quad.subject).collect();
+    assert_eq!(subjects.len(), 2);
+    assert_ne!(subjects[0], subjects[1]);
+}
+
+#[test]
+fn profiles_keep_their_quads_apart() {
+    let nquads = Exporter::new(OutputFormat::NQuads)
+        .export(&profiles())
+        .unwrap();
+    // The subjects of each named graph:
+    let mut graphs: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for line in nquads.lines() {
+        let line = line.strip_suffix(" .").unwrap();
+        let (_, graph) = line.rsplit_once(' ').unwrap();
+        let (subject, _) = line.split_once(' ').unwrap();
+        graphs.entry(graph).or_default().push(subject);
+    }
+    assert_eq!(
+        graphs.keys().copied().collect::<Vec<_>>(),
+        [
+            "<chrome://bookmarks/Default>",
+            "<chrome://bookmarks/Profile%201>"
+        ]
+    );
+    let default = &graphs["<chrome://bookmarks/Default>"];
+    let profile = &graphs["<chrome://bookmarks/Profile%201>"];
+    assert!(default.contains(&"<chrome://bookmarks/Default>"));
+    assert!(profile.contains(&"<chrome://bookmarks/Profile%201>"));
+    assert!(default.iter().all(|subject| !profile.contains(subject)));
+
+    // The default graph of N-Triples is the union of the same quads:
+    let ntriples = Exporter::new(OutputFormat::NTriples)
+        .export(&profiles())
+        .unwrap();
+    assert_eq!(ntriples.lines().count(), nquads.lines().count());
+}
+
+#[test]
+fn schema_folders_have_parts_with_positions() {
+    let transform = BookmarksTransform::new()
+        .unwrap()
+        .with_vocabulary(Vocabulary::Schema);
+    let ntriples = Exporter::new(OutputFormat::NTriples)
+        .with_transform(transform)
+        .export(&profiles()[..1])
+        .unwrap();
+    assert!(!ntriples.contains("22-rdf-syntax-ns#first"));
+
+    let rust = "<urn:uuid:b6d9f1a4-2c3e-4d5f-8a7b-9c0d1e2f3a4b>";
+    for triple in [
+        format!("<chrome://bookmarks/Default#bookmark_bar> <https://schema.org/hasPart> {rust} ."),
+        format!(
+            "{rust} <https://schema.org/hasPart> <urn:uuid:c1d2e3f4-a5b6-4c7d-8e9f-0a1b2c3d4e5f> ."
+        ),
+        format!(
+            "{rust} <https://schema.org/hasPart> <urn:uuid:d4e5f6a7-b8c9-4d0e-9f1a-2b3c4d5e6f70> ."
+        ),
+        format!(
+            "{rust} <https://schema.org/position> \
+             \"1\"^^<http://www.w3.org/2001/XMLSchema#integer> ."
+        ),
+    ] {
+        assert!(ntriples.lines().any(|line| line == triple), "{triple}");
+    }
+    assert!(!ntriples.contains("<https://know.dev/position>"));
+}
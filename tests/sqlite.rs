@@ -0,0 +1,55 @@
+// This is free and unencumbered software released into the public domain.
+
+#![cfg(feature = "sqlite")]
+
+mod common;
+
+use asimov_chromium_module::{browsers::ProfileBookmarks, formats::sqlite};
+use common::fixture;
+use rusqlite::Connection;
+use std::path::{Path, PathBuf};
+
+fn database(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "asimov-chromium-{name}-{}.sqlite",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn row_counts(path: &Path) -> [i64; 4] {
+    let connection = Connection::open(path).unwrap();
+    ["browsers", "profiles", "folders", "bookmarks"].map(|table| {
+        connection
+            .query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+                row.get(0)
+            })
+            .unwrap()
+    })
+}
+
+#[test]
+fn exporting_twice_updates_rows() {
+    let path = database("upsert");
+    let profiles: [ProfileBookmarks; 1] = [fixture("Bookmarks").into()];
+    sqlite::write_catalog(&profiles, &path).unwrap();
+    let counts = row_counts(&path);
+    assert_eq!(counts, [1, 1, 5, 5]);
+
+    sqlite::write_catalog(&profiles, &path).unwrap();
+    assert_eq!(row_counts(&path), counts);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn exporting_deletes_removed_bookmarks() {
+    let path = database("delete");
+    let mut bookmarks = fixture("Bookmarks");
+    sqlite::write_catalog(&[bookmarks.clone().into()], &path).unwrap();
+
+    bookmarks.roots.other.as_mut().unwrap().children.clear();
+    sqlite::write_catalog(&[bookmarks.into()], &path).unwrap();
+    assert_eq!(row_counts(&path), [1, 1, 5, 4]);
+    std::fs::remove_file(&path).unwrap();
+}
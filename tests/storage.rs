@@ -0,0 +1,139 @@
+// This is free and unencumbered software released into the public domain.
+
+mod common;
+
+use asimov_chromium_module::{BookmarkFolder, BookmarkNode, BookmarkStorage, BookmarksFile};
+use common::fixture;
+
+/// The local bookmarks merged with the account bookmarks, as for a profile.
+fn merged() -> BookmarksFile {
+    let mut bookmarks = fixture("Bookmarks");
+    bookmarks.set_storage(BookmarkStorage::Local);
+    let mut account = fixture("AccountBookmarks");
+    account.set_storage(BookmarkStorage::Account);
+    bookmarks.merge(account);
+    bookmarks
+}
+
+/// The names and storage of a folder's children.
+fn children(nodes: &[BookmarkNode]) -> Vec<(&str, Option<BookmarkStorage>)> {
+    nodes
+        .iter()
+        .map(|node| match node {
+            BookmarkNode::Url(url) => (url.name.as_str(), url.storage),
+            BookmarkNode::Folder(folder) => (folder.name.as_str(), folder.storage),
+        })
+        .collect()
+}
+
+#[test]
+fn folders_with_the_same_name_are_merged() {
+    let bookmarks = merged();
+    let bookmark_bar = bookmarks.roots.bookmark_bar.as_ref().unwrap();
+    assert_eq!(bookmark_bar.storage, Some(BookmarkStorage::Both));
+    assert_eq!(
+        children(&bookmark_bar.children),
+        [
+            ("ASIMOV", Some(BookmarkStorage::Both)),
+            ("Rust", Some(BookmarkStorage::Both)),
+        ]
+    );
+
+    let rust = bookmark_bar.children[1].as_folder().unwrap();
+    assert_eq!(
+        children(&rust.children),
+        [
+            (
+                "The Rust Programming Language",
+                Some(BookmarkStorage::Local)
+            ),
+            ("Crates", Some(BookmarkStorage::Local)),
+            ("Rust by Example", Some(BookmarkStorage::Account)),
+        ]
+    );
+    // The merged file no longer matches the stored checksum:
+    assert_eq!(bookmarks.checksum, None);
+}
+
+#[test]
+fn bookmarks_with_the_same_url_are_kept_once() {
+    let bookmarks = merged();
+    let urls: Vec<&str> = bookmarks.urls().map(|url| url.url.as_str()).collect();
+    assert_eq!(
+        urls.iter()
+            .filter(|url| **url == "https://asimov.sh/")
+            .count(),
+        1
+    );
+
+    // The local bookmark is kept, last used at the later of the two times:
+    let asimov = bookmarks.urls().next().unwrap();
+    assert_eq!(
+        asimov.guid.as_deref(),
+        Some("3a6b6ed2-8f0c-4b8f-9f1e-1d2c0f5e6a71")
+    );
+    let local = fixture("Bookmarks");
+    let account = fixture("AccountBookmarks");
+    let last_used = |file: &BookmarksFile| file.urls().next().unwrap().date_last_used;
+    assert_eq!(
+        asimov.date_last_used,
+        last_used(&local).max(last_used(&account))
+    );
+}
+
+#[test]
+fn duplicates_within_a_file_are_kept() {
+    let mut account = fixture("AccountBookmarks");
+    account.set_storage(BookmarkStorage::Account);
+    let bookmark_bar = account.roots.bookmark_bar.as_mut().unwrap();
+    let duplicate = bookmark_bar.children[0].clone();
+    bookmark_bar.children.push(duplicate);
+
+    let mut bookmarks = fixture("Bookmarks");
+    bookmarks.set_storage(BookmarkStorage::Local);
+    bookmarks
+        .roots
+        .bookmark_bar
+        .as_mut()
+        .unwrap()
+        .children
+        .clear();
+    bookmarks.merge(account);
+    let bookmark_bar = bookmarks.roots.bookmark_bar.as_ref().unwrap();
+    assert_eq!(
+        children(&bookmark_bar.children),
+        [
+            ("ASIMOV", Some(BookmarkStorage::Account)),
+            ("Rust", Some(BookmarkStorage::Account)),
+            ("ASIMOV", Some(BookmarkStorage::Account)),
+        ]
+    );
+}
+
+#[test]
+fn roots_in_one_file_keep_its_storage() {
+    let mut bookmarks = fixture("Bookmarks");
+    bookmarks.set_storage(BookmarkStorage::Local);
+    bookmarks.roots.other = None;
+    let mut account = fixture("AccountBookmarks");
+    account.set_storage(BookmarkStorage::Account);
+    account.roots.synced = None;
+    bookmarks.merge(account);
+
+    let roots = &bookmarks.roots;
+    let storage = |folder: Option<&BookmarkFolder>| folder.and_then(|folder| folder.storage);
+    assert_eq!(
+        storage(roots.bookmark_bar.as_ref()),
+        Some(BookmarkStorage::Both)
+    );
+    assert_eq!(
+        storage(roots.other.as_ref()),
+        Some(BookmarkStorage::Account)
+    );
+    assert_eq!(storage(roots.synced.as_ref()), Some(BookmarkStorage::Local));
+    let synced = roots.synced.as_ref().unwrap();
+    assert_eq!(
+        children(&synced.children),
+        [("Café Müller — Über uns", Some(BookmarkStorage::Local))]
+    );
+}
@@ -0,0 +1,179 @@
+// This is free and unencumbered software released into the public domain.
+
+mod common;
+
+use asimov_chromium_module::{
+    BookmarkStorage, BookmarksTransform, ChecksumStatus,
+    browsers::{BookmarksSource, ProfileBookmarks},
+};
+#[cfg(feature = "jq")]
+use asimov_chromium_module::{BookmarksFile, jq};
+use common::fixture;
+use serde_json::{Value, json};
+
+fn chrome_profile() -> ProfileBookmarks {
+    ProfileBookmarks {
+        browser: Some("chrome".into()),
+        profile: Some("Profile 1".into()),
+        sources: vec![BookmarksSource {
+            path: "/home/user/.config/google-chrome/Profile 1/Bookmarks".into(),
+            storage: BookmarkStorage::Local,
+            checksum: ChecksumStatus::Valid,
+            fallback_reason: None,
+        }],
+        bookmarks: fixture("Bookmarks"),
+        warnings: Vec::new(),
+    }
+}
+
+/// Returns the node with the given `@id`, nested in the root folders.
+fn node<'a>(document: &'a Value, id: &str) -> &'a Value {
+    fn find<'a>(nodes: &'a Value, id: &str) -> Option<&'a Value> {
+        nodes.as_array()?.iter().find_map(|node| {
+            (node["@id"] == id)
+                .then_some(node)
+                .or_else(|| find(&node["members"], id))
+        })
+    }
+    find(&document["items"], id).unwrap_or_else(|| panic!("no node {id}"))
+}
+
+/// Returns the `@id`s of the given nodes.
+fn ids(nodes: &Value) -> Vec<&str> {
+    nodes
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|node| node["@id"].as_str().unwrap())
+        .collect()
+}
+
+#[test]
+fn bookmarks_become_know_nodes() {
+    let transform = BookmarksTransform::new().unwrap();
+    let document = transform.execute_bookmarks(&fixture("Bookmarks")).unwrap();
+    assert_eq!(document["@context"]["know"], "https://know.dev/");
+
+    let bookmark = node(&document, "urn:uuid:3a6b6ed2-8f0c-4b8f-9f1e-1d2c0f5e6a71");
+    assert_eq!(bookmark["@type"], "know:Bookmark");
+    assert_eq!(bookmark["title"], "ASIMOV");
+    assert_eq!(bookmark["link"], "https://asimov.sh/");
+    assert_eq!(bookmark["created"], "2025-05-16T03:23:36.783612Z");
+    assert_eq!(bookmark["lastUsed"], "2025-05-18T01:30:12.334918Z");
+    assert_eq!(bookmark["root"], "bookmark_bar");
+
+    // Chromium's zero for "never" is left out:
+    let bookmark = node(&document, "urn:uuid:c1d2e3f4-a5b6-4c7d-8e9f-0a1b2c3d4e5f");
+    assert_eq!(bookmark.get("lastUsed"), None);
+
+    let bookmark = node(&document, "urn:uuid:e5f6a7b8-c9d0-4e1f-8a2b-3c4d5e6f7a81");
+    assert_eq!(
+        bookmark["annotations"],
+        json!([{
+            "@type": "know:Annotation",
+            "key": "last_visited_desktop",
+            "value": "13392005412334918",
+        }])
+    );
+}
+
+#[test]
+fn folders_list_their_members_in_order() {
+    let transform = BookmarksTransform::new().unwrap();
+    let document = transform.execute_bookmarks(&fixture("Bookmarks")).unwrap();
+
+    let root = node(&document, "_:bookmark_bar");
+    assert_eq!(root["@type"], "know:BookmarkFolder");
+    assert_eq!(root["title"], "Bookmarks bar");
+    assert_eq!(root["modified"], "2025-05-16T03:27:03.378910Z");
+    assert_eq!(
+        ids(&root["members"]),
+        [
+            "urn:uuid:3a6b6ed2-8f0c-4b8f-9f1e-1d2c0f5e6a71",
+            "urn:uuid:b6d9f1a4-2c3e-4d5f-8a7b-9c0d1e2f3a4b",
+        ]
+    );
+    assert_eq!(root.get("folder"), None);
+
+    let folder = node(&document, "urn:uuid:b6d9f1a4-2c3e-4d5f-8a7b-9c0d1e2f3a4b");
+    assert_eq!(folder["@type"], "know:BookmarkFolder");
+    assert_eq!(folder["folder"], "_:bookmark_bar");
+    assert_eq!(folder["position"], 1);
+    let crates = node(&document, "urn:uuid:d4e5f6a7-b8c9-4d0e-9f1a-2b3c4d5e6f70");
+    assert_eq!(crates["folder"], folder["@id"]);
+    assert_eq!(folder["members"][1]["@id"], crates["@id"]);
+}
+
+#[test]
+fn profiles_are_identified_by_their_url() {
+    let transform = BookmarksTransform::new().unwrap();
+    let document = transform.execute_profile(&chrome_profile()).unwrap();
+    assert_eq!(document["@id"], "chrome://bookmarks/Profile%201");
+    assert_eq!(document["@type"], "know:BrowserProfile");
+    assert_eq!(document["browser"], "chrome");
+    assert_eq!(document["label"], "Profile 1");
+    assert_eq!(
+        document["@context"]["label"],
+        json!({ "@id": "rdfs:label" })
+    );
+    assert_eq!(
+        document["source"],
+        json!(["file:///home/user/.config/google-chrome/Profile%201/Bookmarks"])
+    );
+    // Only the root folders are linked to the profile:
+    assert_eq!(
+        ids(&document["items"]),
+        [
+            "chrome://bookmarks/Profile%201#bookmark_bar",
+            "chrome://bookmarks/Profile%201#other",
+            "chrome://bookmarks/Profile%201#synced",
+        ]
+    );
+    let root = node(&document, "chrome://bookmarks/Profile%201#other");
+    assert_eq!(root["root"], "other");
+}
+
+#[cfg(feature = "jq")]
+fn assert_same_output(bookmarks: &BookmarksFile) {
+    let native = BookmarksTransform::new().unwrap();
+    let filter = BookmarksTransform::with_jq(jq::BOOKMARKS).unwrap();
+    assert_eq!(
+        native.execute_bookmarks(bookmarks).unwrap().to_string(),
+        filter.execute_bookmarks(bookmarks).unwrap().to_string(),
+    );
+}
+
+#[cfg(feature = "jq")]
+#[test]
+fn native_matches_jq_for_local_bookmarks() {
+    assert_same_output(&fixture("Bookmarks"));
+}
+
+#[cfg(feature = "jq")]
+#[test]
+fn native_matches_jq_for_account_bookmarks() {
+    assert_same_output(&fixture("AccountBookmarks"));
+}
+
+#[cfg(feature = "jq")]
+#[test]
+fn native_matches_jq_for_merged_bookmarks() {
+    let mut bookmarks = fixture("Bookmarks");
+    bookmarks.set_storage(BookmarkStorage::Local);
+    let mut account = fixture("AccountBookmarks");
+    account.set_storage(BookmarkStorage::Account);
+    bookmarks.merge(account);
+    assert_same_output(&bookmarks);
+}
+
+#[cfg(feature = "jq")]
+#[test]
+fn native_matches_jq_for_a_browser_profile() {
+    let profile = chrome_profile();
+    let native = BookmarksTransform::new().unwrap();
+    let filter = BookmarksTransform::with_jq(jq::BOOKMARKS).unwrap();
+    assert_eq!(
+        native.execute_profile(&profile).unwrap().to_string(),
+        filter.execute_profile(&profile).unwrap().to_string(),
+    );
+}
@@ -0,0 +1,76 @@
+// This is free and unencumbered software released into the public domain.
+
+mod common;
+
+use asimov_chromium_module::{
+    BookmarksFile, BookmarksTransform, ChecksumStatus, Vocabulary, writer,
+};
+use common::fixture;
+use serde_json::Value;
+
+#[test]
+fn jsonld_round_trip_writes_a_valid_file() {
+    let bookmarks = fixture("Bookmarks");
+    let transform = BookmarksTransform::new().unwrap();
+    let document = transform.execute_bookmarks(&bookmarks).unwrap();
+
+    let written = writer::prepare(&writer::from_jsonld(&document).unwrap(), 0);
+    assert_eq!(written.checksum_status(), ChecksumStatus::Valid);
+    assert_eq!(transform.execute_bookmarks(&written).unwrap(), document);
+
+    let bookmark_bar = written.roots.bookmark_bar.as_ref().unwrap();
+    assert_eq!(bookmark_bar.id.as_deref(), Some("1"));
+    assert_eq!(
+        bookmark_bar.children[0].as_url().unwrap().id.as_deref(),
+        Some("4")
+    );
+}
+
+#[test]
+fn folder_contents_are_ordered_by_position() {
+    let bookmarks = fixture("Bookmarks");
+    let transform = BookmarksTransform::new()
+        .unwrap()
+        .with_vocabulary(Vocabulary::Schema);
+    let mut document = transform.execute_bookmarks(&bookmarks).unwrap();
+    fn reverse(nodes: &mut Value) {
+        if let Some(nodes) = nodes.as_array_mut() {
+            nodes.reverse();
+            for node in nodes {
+                reverse(&mut node["hasPart"]);
+            }
+        }
+    }
+    reverse(&mut document["items"]);
+
+    let written = writer::from_jsonld(&document).unwrap();
+    let names = |bookmarks: &BookmarksFile| -> Vec<String> {
+        let folder = bookmarks.roots.bookmark_bar.as_ref().unwrap();
+        folder
+            .children
+            .iter()
+            .map(|child| child.name().to_string())
+            .collect()
+    };
+    assert_eq!(names(&written), names(&bookmarks));
+}
+
+#[test]
+fn files_are_replaced_with_a_backup() {
+    let directory =
+        std::env::temp_dir().join(format!("asimov-chromium-writer-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    std::fs::create_dir_all(&directory).unwrap();
+    let path = directory.join("Bookmarks");
+    std::fs::write(&path, "previous").unwrap();
+
+    writer::write_file(&path, &fixture("Bookmarks")).unwrap();
+    let written: BookmarksFile =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(written.checksum_status(), ChecksumStatus::Valid);
+    assert_eq!(
+        std::fs::read_to_string(directory.join("Bookmarks.bak")).unwrap(),
+        "previous"
+    );
+    assert!(!directory.join("Bookmarks.tmp").exists());
+}